@@ -1,4 +1,8 @@
 use std::future::Future;
+use std::task::Poll;
+use std::time::Duration;
+
+use crate::sleep::sleep;
 
 pub(crate) enum State {
     Empty,
@@ -69,3 +73,53 @@ pub fn block_on<F: std::future::IntoFuture>(fut: F) -> F::Output {
         }
     }
 }
+
+/// Returned by [`block_on_timeout`] when the future didn't complete in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "future timed out")
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Like [`block_on`], but gives up once `dur` has elapsed. The future is dropped then
+pub fn block_on_timeout<F: std::future::IntoFuture>(fut: F, dur: Duration) -> Result<F::Output, TimedOut> {
+    let mut fut = core::pin::pin!(fut.into_future());
+    let mut timer = core::pin::pin!(sleep(dur));
+
+    block_on(std::future::poll_fn(|cx| {
+        if let Poll::Ready(item) = fut.as_mut().poll(cx) {
+            return Poll::Ready(Ok(item));
+        }
+
+        timer.as_mut().poll(cx).map(|_| Err(TimedOut))
+    }))
+}
+
+#[cfg(test)]
+mod block_on_test {
+    use std::time::Instant;
+    use super::*;
+
+    #[test]
+    fn ready_before_timeout() {
+        let res = block_on_timeout(async { 69 }, Duration::from_secs(1));
+        assert_eq!(res, Ok(69));
+    }
+
+    #[test]
+    fn never_ready_times_out() {
+        let dur = Duration::from_millis(50);
+        let start = Instant::now();
+
+        let res = block_on_timeout(std::future::pending::<()>(), dur);
+
+        assert_eq!(res, Err(TimedOut));
+        assert!(start.elapsed() >= dur);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
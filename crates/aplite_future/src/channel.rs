@@ -1,6 +1,7 @@
-use std::sync::{Arc, Weak, RwLock};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::task::{Context, Poll, Wake};
+use std::collections::VecDeque;
+use std::sync::{Arc, Weak, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::task::{Context, Poll, Wake, Waker};
 use std::pin::Pin;
 
 use crate::stream::*;
@@ -93,24 +94,28 @@ impl Rx {
 
 pub fn async_channel<T>() -> (Sender<T>, Receiver<T>) {
     let inner = Arc::new(ChannelState::default());
-    let rx = Receiver(Arc::downgrade(&inner));
+    let rx = Receiver(Arc::clone(&inner));
     let tx = Sender(inner);
     (tx, rx)
 }
 
 pub struct Sender<T>(Arc<ChannelState<T>>);
 
-pub struct Receiver<T>(Weak<ChannelState<T>>);
+/// Receives the values in the order they were sent. The channel is closed once every [`Sender`] is dropped,
+/// the values sent before that are still received
+pub struct Receiver<T>(Arc<ChannelState<T>>);
 
 struct ChannelState<T> {
-    value: RwLock<Option<T>>,
+    queue: Mutex<VecDeque<T>>,
+    senders: AtomicUsize,
     waker: AtomicWaker,
 }
 
 impl<T> Default for ChannelState<T> {
     fn default() -> Self {
         Self {
-            value: RwLock::new(None),
+            queue: Mutex::new(VecDeque::new()),
+            senders: AtomicUsize::new(1),
             waker: AtomicWaker::new(),
         }
     }
@@ -118,7 +123,7 @@ impl<T> Default for ChannelState<T> {
 
 impl<T> Sender<T> {
     pub fn notify(&self, value: T) {
-        *self.0.value.write().unwrap() = Some(value);
+        self.0.queue.lock().unwrap().push_back(value);
         self.0.wake_by_ref();
     }
 
@@ -129,15 +134,15 @@ impl<T> Sender<T> {
 
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
-        if std::mem::needs_drop::<T>()
-            && let Some(pending_data) = self.0.value.write().unwrap().take() {
-            drop(pending_data)
+        if self.0.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.wake_by_ref();
         }
     }
 }
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
+        self.0.senders.fetch_add(1, Ordering::AcqRel);
         Self(Arc::clone(&self.0))
     }
 }
@@ -154,31 +159,217 @@ impl<T> Wake for ChannelState<T> {
     }
 }
 
+impl<T> ChannelState<T> {
+    fn is_closed(&self) -> bool {
+        self.senders.load(Ordering::Acquire) == 0
+    }
+}
+
 impl<T> Stream for Receiver<T> {
     type Item = T;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match self.0.upgrade() {
-            Some(inner) => {
-                inner.waker.set(cx.waker());
+        let inner = &self.0;
+        inner.waker.set(cx.waker());
+
+        // checked before popping, a value sent right before closing is still received
+        let closed = inner.is_closed();
+
+        if let Some(val) = inner.queue.lock().unwrap().pop_front() {
+            Poll::Ready(Some(val))
+        } else if closed {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
 
-                if let Some(val) = inner.value.write().unwrap().take() {
-                    Poll::Ready(Some(val))
-                } else {
-                    Poll::Pending
-                }
-            },
-            None => Poll::Ready(None),
+/// Returned by [`Receiver::try_recv`] when there's no value to take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// Nothing has been sent yet
+    Empty,
+    /// Every [`Sender`] is dropped and no value is left
+    Disconnected,
+}
+
+impl std::fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "channel is empty"),
+            Self::Disconnected => write!(f, "channel is closed"),
         }
     }
 }
 
+impl std::error::Error for TryRecvError {}
+
 impl<T> Receiver<T> {
+    /// Take the next value without waiting, e.g. from the render loop
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let closed = self.0.is_closed();
+
+        match self.0.queue.lock().unwrap().pop_front() {
+            Some(val) => Ok(val),
+            None if closed => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Whether every [`Sender`] is dropped. Values sent before that can still be received
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    pub fn sender_count(&self) -> usize {
+        self.0.senders.load(Ordering::Acquire)
+    }
+
     pub fn recv(&mut self) -> impl Future<Output = Option<<Self as Stream>::Item>> {
         crate::stream::Recv {
             inner: Pin::new(self),
         }
     }
+
+    /// Consume the values as a [`Stream`], which ends once the channel is closed
+    pub fn into_stream(self) -> impl Stream<Item = T> + Unpin {
+        self
+    }
+}
+
+/*
+#########################################################
+#                                                       #
+#                     Watch Channel                     #
+#                                                       #
+#########################################################
+*/
+
+/// A channel holding only the latest value. Every [`WatchReceiver`] sees the newest one,
+/// updates made in between two reads are skipped
+pub fn watch<T: Clone>(initial: T) -> (WatchSender<T>, WatchReceiver<T>) {
+    let inner = Arc::new(WatchState {
+        value: RwLock::new(initial),
+        version: AtomicU64::new(0),
+        senders: AtomicUsize::new(1),
+        wakers: Mutex::new(Vec::new()),
+    });
+
+    let rx = WatchReceiver {
+        inner: Arc::clone(&inner),
+        seen: 0,
+    };
+
+    (WatchSender(inner), rx)
+}
+
+pub struct WatchSender<T>(Arc<WatchState<T>>);
+
+pub struct WatchReceiver<T> {
+    inner: Arc<WatchState<T>>,
+    /// Version of the last value this receiver has seen
+    seen: u64,
+}
+
+struct WatchState<T> {
+    value: RwLock<T>,
+    version: AtomicU64,
+    senders: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl<T> WatchState<T> {
+    fn wake_all(&self) {
+        self.wakers
+            .lock()
+            .unwrap()
+            .drain(..)
+            .for_each(Waker::wake);
+    }
+}
+
+impl<T> WatchSender<T> {
+    /// Replace the stored value and notify every receiver
+    pub fn send(&self, value: T) {
+        *self.0.value.write().unwrap() = value;
+        self.0.version.fetch_add(1, Ordering::AcqRel);
+        self.0.wake_all();
+    }
+
+    pub fn close(self) {
+        drop(self)
+    }
+}
+
+impl<T> Clone for WatchSender<T> {
+    fn clone(&self) -> Self {
+        self.0.senders.fetch_add(1, Ordering::AcqRel);
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Drop for WatchSender<T> {
+    fn drop(&mut self) {
+        if self.0.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.wake_all();
+        }
+    }
+}
+
+impl<T: Clone> WatchReceiver<T> {
+    /// The latest value, marking it as seen
+    pub fn get(&mut self) -> T {
+        self.seen = self.inner.version.load(Ordering::Acquire);
+        self.inner.value.read().unwrap().clone()
+    }
+
+    pub fn has_changed(&self) -> bool {
+        self.inner.version.load(Ordering::Acquire) != self.seen
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.senders.load(Ordering::Acquire) == 0
+    }
+
+    /// Wait for a value newer than the last seen one, [`None`] once every [`WatchSender`] is dropped
+    pub fn changed(&mut self) -> impl Future<Output = Option<<Self as Stream>::Item>> {
+        crate::stream::Recv {
+            inner: Pin::new(self),
+        }
+    }
+}
+
+impl<T> Clone for WatchReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            seen: self.seen,
+        }
+    }
+}
+
+impl<T: Clone> Stream for WatchReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        {
+            let mut wakers = this.inner.wakers.lock().unwrap();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+
+        if this.has_changed() {
+            Poll::Ready(Some(this.get()))
+        } else if this.is_closed() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
 }
 
 /*
@@ -196,6 +387,73 @@ mod channel_test {
     use crate::executor::Executor;
     use crate::sleep::sleep;
 
+    #[test]
+    fn receiver_into_stream() {
+        let (tx, rx) = async_channel::<u32>();
+
+        for i in 1..=3 {
+            tx.notify(i);
+        }
+        tx.close();
+
+        let mut stream = rx.into_stream().map(|i| i * 10);
+        let collected = crate::block_on(async {
+            let mut items = vec![];
+            while let Some(item) = stream.next().await {
+                items.push(item);
+            }
+            items
+        });
+
+        assert_eq!(collected, [10, 20, 30]);
+    }
+
+    #[test]
+    fn try_recv_states() {
+        let (tx, rx) = async_channel::<u32>();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert!(!rx.is_closed());
+
+        let other = tx.clone();
+        assert_eq!(rx.sender_count(), 2);
+
+        tx.notify(69);
+        assert_eq!(rx.try_recv(), Ok(69));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        other.notify(420);
+        drop(tx);
+        drop(other);
+        assert!(rx.is_closed());
+        assert_eq!(rx.sender_count(), 0);
+
+        // values sent before closing are kept
+        assert_eq!(rx.try_recv(), Ok(420));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn watch_skips_to_latest() {
+        let (tx, mut rx) = watch(0u32);
+        let mut other = rx.clone();
+        assert!(!rx.has_changed());
+        assert_eq!(rx.get(), 0);
+
+        for i in 1..=3 {
+            tx.send(i);
+        }
+
+        assert!(rx.has_changed());
+        assert_eq!(crate::block_on(rx.changed()), Some(3));
+        assert!(!rx.has_changed());
+
+        // every receiver sees the latest value on its own
+        assert_eq!(other.get(), 3);
+
+        tx.close();
+        assert_eq!(crate::block_on(rx.changed()), None);
+    }
+
     #[test]
     fn poll() {
         let (tx, mut rx) = notifier();
@@ -3,7 +3,7 @@ use std::sync::mpsc::{SyncSender, Receiver, sync_channel};
 use std::task::{Waker, Context, Poll};
 use std::thread;
 
-use crate::task::Task;
+use crate::task::{RawTask, Task};
 
 /*
 #########################################################
@@ -16,10 +16,10 @@ use crate::task::Task;
 pub(crate) static SPAWNER: OnceLock<Spawner> = const { OnceLock::new() };
 
 #[derive(Debug)]
-pub(crate) struct Spawner(SyncSender<Arc<Task>>);
+pub(crate) struct Spawner(SyncSender<Arc<RawTask>>);
 
 impl Spawner {
-    pub(crate) fn send(&self, task: Arc<Task>) {
+    pub(crate) fn send(&self, task: Arc<RawTask>) {
         self.0.send(task).unwrap()
     }
 }
@@ -28,13 +28,18 @@ unsafe impl Send for Spawner {}
 unsafe impl Sync for Spawner {}
 
 struct Worker {
-    rx: Receiver<Arc<Task>>,
+    rx: Receiver<Arc<RawTask>>,
 }
 
 impl Worker {
     fn work(&self) {
         while let Ok(task) = self.rx.recv() {
             if let Ok(mut future) = task.future.write() {
+                if task.is_cancelled() {
+                    *future = Box::pin(std::future::ready(()));
+                    continue;
+                }
+
                 let waker = Waker::from(Arc::clone(&task));
                 let cx = &mut Context::from_waker(&waker);
 
@@ -55,7 +60,8 @@ unsafe impl Sync for Worker {}
 pub struct Executor;
 
 impl Executor {
-    pub fn spawn(future: impl Future<Output = ()> + 'static) {
+    /// Run the future on the worker thread. The returned [`Task`] can cancel it, dropping it doesn't
+    pub fn spawn(future: impl Future<Output = ()> + 'static) -> Task {
         let spawner = SPAWNER.get_or_init(|| {
             let (tx, rx) = sync_channel(128);
             let worker = Worker { rx };
@@ -66,8 +72,10 @@ impl Executor {
             Spawner(tx)
         });
 
-        let task = Arc::new(Task::new(future));
-        spawner.send(task);
+        let task = Arc::new(RawTask::new(future));
+        spawner.send(Arc::clone(&task));
+
+        Task::new(task)
     }
 }
 
@@ -95,4 +103,56 @@ mod executor_test {
 
         std::thread::sleep(std::time::Duration::from_secs(1));
     }
+
+    fn wait_for(flag: &std::sync::atomic::AtomicBool) -> bool {
+        let start = std::time::Instant::now();
+        while start.elapsed() < std::time::Duration::from_secs(2) {
+            if flag.load(std::sync::atomic::Ordering::Acquire) { return true }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn cancelled_task_stops() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let task = Executor::spawn({
+            let ticks = Arc::clone(&ticks);
+            async move {
+                loop {
+                    ticks.fetch_add(1, Ordering::AcqRel);
+                    crate::sleep(Duration::from_millis(5)).await;
+                }
+            }
+        });
+
+        std::thread::sleep(Duration::from_millis(50));
+        task.cancel();
+        std::thread::sleep(Duration::from_millis(20));
+
+        let stopped_at = ticks.load(Ordering::Acquire);
+        assert!(stopped_at > 0);
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(ticks.load(Ordering::Acquire), stopped_at);
+    }
+
+    #[test]
+    fn detached_task_completes() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let done = Arc::new(AtomicBool::new(false));
+        drop(Executor::spawn({
+            let done = Arc::clone(&done);
+            async move {
+                crate::sleep(std::time::Duration::from_millis(20)).await;
+                done.store(true, Ordering::Release);
+            }
+        }));
+
+        assert!(wait_for(&done));
+    }
 }
@@ -6,8 +6,9 @@ mod stream;
 mod task;
 mod waker;
 
-pub use block_on::block_on;
+pub use block_on::{block_on, block_on_timeout, TimedOut};
 pub use sleep::*;
 pub use executor::Executor;
+pub use task::Task;
 pub use channel::*;
 pub use stream::*;
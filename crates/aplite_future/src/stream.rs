@@ -5,6 +5,39 @@ pub trait Stream {
     type Item;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+
+    /// Resolves to the next item, or [`None`] once the stream has ended
+    fn next(&mut self) -> Recv<'_, Self>
+    where
+        Self: Unpin + Sized,
+    {
+        Recv {
+            inner: Pin::new(self),
+        }
+    }
+
+    fn map<U, F>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> U,
+    {
+        Map {
+            stream: self,
+            f,
+        }
+    }
+
+    /// Skip the items for which `f` returns false
+    fn filter<F>(self, f: F) -> Filter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> bool,
+    {
+        Filter {
+            stream: self,
+            f,
+        }
+    }
 }
 
 impl<T> Stream for Pin<T>
@@ -41,3 +74,47 @@ impl<S: Stream> Future for Recv<'_, S> {
         self.get_mut().inner.as_mut().poll_next(cx)
     }
 }
+
+pub struct Map<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F, U> Stream for Map<S, F>
+where
+    S: Stream + Unpin,
+    F: FnMut(S::Item) -> U + Unpin,
+{
+    type Item = U;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.stream)
+            .poll_next(cx)
+            .map(|item| item.map(&mut this.f))
+    }
+}
+
+pub struct Filter<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> Stream for Filter<S, F>
+where
+    S: Stream + Unpin,
+    F: FnMut(&S::Item) -> bool + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(item)) if !(this.f)(&item) => continue,
+                poll => return poll,
+            }
+        }
+    }
+}
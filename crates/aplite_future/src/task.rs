@@ -1,32 +1,61 @@
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::Wake;
 
 use crate::executor::SPAWNER;
 
 type PinnedFuture = Pin<Box<dyn Future<Output = ()>>>;
- 
-pub(crate) struct Task {
+
+/// Handle to a future spawned via [`Executor::spawn`](crate::Executor::spawn).
+/// Dropping it detaches the future, which keeps running until completion
+pub struct Task(Arc<RawTask>);
+
+impl Task {
+    pub(crate) fn new(raw: Arc<RawTask>) -> Self {
+        Self(raw)
+    }
+
+    /// Stop the future, it won't be polled again and gets dropped by the worker
+    pub fn cancel(self) {
+        if !self.0.cancelled.swap(true, Ordering::AcqRel) {
+            // let the worker drop the future on its own thread
+            Arc::clone(&self.0).wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+pub(crate) struct RawTask {
     pub(crate) future: RwLock<PinnedFuture>,
+    cancelled: AtomicBool,
 }
 
-impl Task {
+impl RawTask {
     pub(crate) fn new<F>(future: F) -> Self
     where
         F: Future<Output = ()> + 'static,
     {
         Self {
             future: RwLock::new(Box::pin(future)),
+            cancelled: AtomicBool::new(false),
         }
     }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
 }
 
-impl Wake for Task {
+impl Wake for RawTask {
     fn wake(self: Arc<Self>) {
         let spawner = SPAWNER.get().unwrap();
         spawner.send(self);
     }
 }
 
-unsafe impl Send for Task {}
-unsafe impl Sync for Task {}
+unsafe impl Send for RawTask {}
+unsafe impl Sync for RawTask {}
@@ -38,6 +38,7 @@ impl Effect {
         let scope = Scope::new();
         let effect_state = EffectState::new(tx);
         let this = effect_state.to_any_subscriber();
+        let state = Arc::downgrade(&effect_state);
         let node = ReactiveStorage::insert(effect_state);
 
         Executor::spawn(async move {
@@ -49,10 +50,21 @@ impl Effect {
 
                     let prev_value = value.take();
 
+                    EffectState::set_running(&state, true);
                     let new_value = scope.with_cleanup(|| {
                         this.as_observer(|| f(prev_value))
                     });
 
+                    if EffectState::set_running(&state, false) {
+                        #[cfg(debug_assertions)]
+                        eprintln!("[effect] {node:?} triggered itself while running, the rerun is skipped to break the cycle");
+
+                        // the sources dropped this effect when they notified it
+                        if let Some(state) = state.upgrade() {
+                            state.read().unwrap().source.subscribe(&this);
+                        }
+                    }
+
                     value = Some(new_value);
                 }
             }
@@ -67,12 +79,25 @@ impl Effect {
     pub fn stop(self) {
         ReactiveStorage::remove(self.node);
     }
+
+    /// Whether the effect has ever triggered itself while running, directly or through other nodes
+    pub fn cycle_detected(&self) -> bool {
+        ReactiveStorage::try_with_downcast(&self.node, |state| {
+            state.read().ok().map(|state| state.cycle_detected)
+        })
+        .unwrap_or_default()
+    }
 }
 
 struct EffectState {
     sender: Notifier,
     source: Sources,
     dirty: bool,
+    /// The thread running the effect right now. Being notified from it means the effect triggered itself
+    running: Option<std::thread::ThreadId>,
+    /// Set when a cycle is hit during the current run
+    cycle: bool,
+    cycle_detected: bool,
 }
 
 unsafe impl Send for EffectState {}
@@ -84,8 +109,22 @@ impl EffectState {
             sender,
             source: Sources::default(),
             dirty: true,
+            running: None,
+            cycle: false,
+            cycle_detected: false,
         }))
     }
+
+    /// Returns whether the effect has triggered itself since it started running
+    fn set_running(state: &std::sync::Weak<RwLock<Self>>, running: bool) -> bool {
+        let Some(state) = state.upgrade() else { return false };
+        let mut state = state.write().unwrap();
+
+        state.running = running.then(|| std::thread::current().id());
+        let cycle = std::mem::take(&mut state.cycle);
+        state.cycle_detected |= cycle;
+        cycle
+    }
 }
 
 impl Drop for EffectState {
@@ -122,6 +161,12 @@ impl Subscriber for Arc<RwLock<EffectState>> {
 impl Reactive for RwLock<EffectState> {
     fn mark_dirty(&self) {
         let this = &mut *self.write().unwrap();
+
+        if this.running.is_some_and(|id| id == std::thread::current().id()) {
+            this.cycle = true;
+            return;
+        }
+
         this.dirty = true;
         this.sender.notify();
     }
@@ -236,4 +281,69 @@ mod effect_test {
 
         std::thread::sleep(std::time::Duration::from_millis(delta * 9));
     }
+
+    #[test]
+    fn self_triggering_effect_terminates() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let count = Signal::new(0u32);
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let effect = Effect::new({
+            let runs = Arc::clone(&runs);
+            move |_| {
+                runs.fetch_add(1, Ordering::AcqRel);
+                let num = count.get();
+                count.set(num + 1);
+            }
+        });
+
+        let delta = std::time::Duration::from_millis(100);
+        std::thread::sleep(delta);
+        assert_eq!(runs.load(Ordering::Acquire), 1);
+        assert_eq!(count.get_untracked(), 1);
+        assert!(effect.cycle_detected());
+
+        // still subscribed after the cycle is broken
+        count.set(10);
+        std::thread::sleep(delta);
+        assert_eq!(runs.load(Ordering::Acquire), 2);
+        assert_eq!(count.get_untracked(), 11);
+    }
+
+    #[test]
+    fn tuple_with() {
+        use std::sync::Mutex;
+
+        let first = Signal::new("Mario");
+        let (last, set_last) = Signal::split("Kempes");
+        let age = Signal::new(20u32);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        Effect::new({
+            let seen = Arc::clone(&seen);
+            move |_| {
+                let line = (first, last, age).with(|(first, last, age)| format!("{first} {last} {age}"));
+                seen.lock().unwrap().push(line);
+            }
+        });
+
+        let delta = std::time::Duration::from_millis(100);
+        std::thread::sleep(delta);
+
+        first.set("Dario");
+        std::thread::sleep(delta);
+        set_last.set("Bros");
+        std::thread::sleep(delta);
+        age.set(21);
+        std::thread::sleep(delta);
+
+        assert_eq!(*seen.lock().unwrap(), [
+            "Mario Kempes 20",
+            "Dario Kempes 20",
+            "Dario Bros 20",
+            "Dario Bros 21",
+        ]);
+        assert_eq!((first, age).get_untracked(), ("Dario", 21));
+    }
 }
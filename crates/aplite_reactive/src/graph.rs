@@ -31,6 +31,20 @@ struct ReactiveScope {
     parent: Option<WeakScope>,
     children: Vec<WeakScope>,
     node_ids: Vec<SlotId>,
+    cleanups: Vec<Box<dyn FnOnce()>>,
+}
+
+unsafe impl Send for ReactiveScope {}
+unsafe impl Sync for ReactiveScope {}
+
+/// Register a closure to run once when the current scope is cleaned up, either when it reruns or gets disposed.
+/// Outside of any scope the closure is dropped without running
+pub fn on_cleanup(f: impl FnOnce() + 'static) {
+    Scope::with_current(|scope| {
+        if let Some(scope) = scope.upgrade() {
+            scope.0.write().unwrap().cleanups.push(Box::new(f));
+        }
+    });
 }
 
 impl Scope {
@@ -53,6 +67,7 @@ impl Scope {
                 parent: current_scope,
                 children: Vec::new(),
                 node_ids: Vec::new(),
+                cleanups: Vec::new(),
             })
         }))
     }
@@ -81,6 +96,8 @@ impl Scope {
 
         let children = std::mem::take(&mut lock.children);
         let node_ids = std::mem::take(&mut lock.node_ids);
+        let cleanups = std::mem::take(&mut lock.cleanups);
+        drop(lock);
 
         for child in children {
             if let Some(child) = child.upgrade() {
@@ -88,6 +105,8 @@ impl Scope {
             }
         }
 
+        cleanups.into_iter().for_each(|f| f());
+
         ReactiveStorage::with_mut(|graph| {
             for id in node_ids {
                 graph.inner.remove(id);
@@ -343,3 +362,23 @@ impl<R> std::fmt::Debug for Node<R> {
     }
 }
 
+#[cfg(test)]
+mod scope_test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::*;
+
+    #[test]
+    fn cleanup_runs_once() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let scope = Scope::new();
+        scope.with(|| on_cleanup(|| {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+        }));
+        assert_eq!(COUNT.load(Ordering::Relaxed), 0);
+
+        scope.cleanup();
+        scope.cleanup();
+        assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+    }
+}
@@ -7,17 +7,20 @@ mod signal_read;
 mod signal_write;
 mod source;
 mod subscriber;
+mod tween;
 
 pub use effect::*;
-pub use graph::Scope;
+pub use graph::{Scope, on_cleanup};
 pub use memo::*;
 pub use signal::*;
 pub use signal_read::*;
 pub use signal_write::*;
+pub use tween::*;
 pub use reactive_traits::{
     Dispose,
     Get,
     With,
+    WithAll,
     Update,
     Set,
 };
@@ -181,6 +181,17 @@ impl<T: 'static> Memo<T> {
 
         Self { node: ReactiveStorage::insert(state) }
     }
+
+    /// Like [`Memo::new`], but `eq` decides whether the recomputed value is the same as the previous one,
+    /// e.g. floats compared with a tolerance. Equal values aren't reported as a change to the subscribers
+    pub fn new_with_eq(
+        f: impl Fn(Option<&T>) -> T + 'static,
+        eq: impl Fn(&T, &T) -> bool + 'static,
+    ) -> Self {
+        Self::with_compare(f, move |prev, new| {
+            !prev.zip(new).is_some_and(|(prev, new)| eq(prev, new))
+        })
+    }
 }
 
 impl<T: 'static> Dispose for Memo<T> {
@@ -379,6 +390,25 @@ mod memo_test {
         std::thread::sleep(std::time::Duration::from_millis(delta * 4));
     }
 
+    #[test]
+    fn epsilon_equality() {
+        let value = Signal::new(1.0f32);
+        let memo = Memo::new_with_eq(move |_| value.get(), |prev, new| (prev - new).abs() < 0.01);
+
+        // the first computation is always a change
+        assert!(memo.try_update());
+        assert_eq!(memo.get_untracked(), 1.0);
+
+        value.set(1.001);
+        memo.mark_dirty();
+        assert!(!memo.try_update());
+
+        value.set(2.0);
+        memo.mark_dirty();
+        assert!(memo.try_update());
+        assert_eq!(memo.get_untracked(), 2.0);
+    }
+
     #[test]
     fn parent_child() {
         let name = Signal::new("Signal");
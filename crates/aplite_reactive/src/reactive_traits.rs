@@ -103,6 +103,85 @@ pub trait With: Track {
         F: FnOnce(&Self::Value) -> R;
 }
 
+/// Read several reactive nodes at once, e.g. `(a, b, c).with(|(a, b, c)| ..)`.
+/// Implemented for tuples of up to 8 [`With`] nodes, tracking all of them
+pub trait WithAll: Track {
+    type Refs<'a>;
+
+    fn with<F, R>(&self, f: F) -> R
+    where
+        F: for<'a> FnOnce(Self::Refs<'a>) -> R
+    {
+        self.track();
+        self.with_untracked(f)
+    }
+
+    fn with_untracked<F, R>(&self, f: F) -> R
+    where
+        F: for<'a> FnOnce(Self::Refs<'a>) -> R;
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Track),*> Track for ($($name,)*) {
+            fn track(&self) {
+                let ($($name,)*) = self;
+                $($name.track();)*
+            }
+
+            fn untrack(&self) {
+                let ($($name,)*) = self;
+                $($name.untrack();)*
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<$($name: Get),*> Get for ($($name,)*) {
+            type Value = ($($name::Value,)*);
+
+            fn get_untracked(&self) -> Self::Value {
+                let ($($name,)*) = self;
+                ($($name.get_untracked(),)*)
+            }
+
+            fn try_get_untracked(&self) -> Option<Self::Value> {
+                let ($($name,)*) = self;
+                Some(($($name.try_get_untracked()?,)*))
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<$($name: With),*> WithAll for ($($name,)*) {
+            type Refs<'a> = ($(&'a $name::Value,)*);
+
+            fn with_untracked<F, R>(&self, f: F) -> R
+            where
+                F: for<'a> FnOnce(Self::Refs<'a>) -> R
+            {
+                let ($($name,)*) = self;
+                impl_tuple!(@nest f; (); $($name),*)
+            }
+        }
+    };
+
+    (@nest $f:ident; ($($value:ident),*); $name:ident $(, $rest:ident)*) => {
+        $name.with_untracked(|$name| impl_tuple!(@nest $f; ($($value,)* $name); $($rest),*))
+    };
+
+    (@nest $f:ident; ($($value:ident),*);) => {
+        $f(($($value,)*))
+    };
+}
+
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F0);
+impl_tuple!(A, B, C, D, E, F0, G);
+impl_tuple!(A, B, C, D, E, F0, G, H);
+
 /*
 #########################################################
 #                                                       #
@@ -132,6 +211,14 @@ pub trait Update: Notify {
         self.notify();
     }
 
+    /// update the value via a closure, notify the subscribers and return what the closure returned
+    fn update_returning<U>(&self, f: impl FnOnce(&mut Self::Value) -> U) -> U {
+        let mut ret = None;
+        self.update_untracked(|value| ret = Some(f(value)));
+        self.notify();
+        ret.unwrap()
+    }
+
     fn update_untracked(&self, f: impl FnOnce(&mut Self::Value));
 }
 
@@ -28,6 +28,14 @@ pub(crate) type SignalNode<T> = Node<Arc<RwLock<SignalState<T>>>>;
 pub(crate) struct SignalState<T> {
     pub(crate) value: T,
     pub(crate) subscribers: Subscribers,
+    pub(crate) name: Option<SignalName<T>>,
+}
+
+/// Given via [`Signal::named`], along with the formatter of the value for the change log
+pub(crate) struct SignalName<T> {
+    name: &'static str,
+    #[cfg_attr(not(any(test, feature = "signal_log")), allow(dead_code))]
+    format: fn(&T) -> String,
 }
 
 unsafe impl<T> Send for SignalState<T> {}
@@ -38,6 +46,7 @@ impl<T: 'static> SignalState<T> {
         Arc::new(RwLock::new(Self {
             value,
             subscribers: Subscribers::default(),
+            name: None,
         }))
     }
 }
@@ -103,6 +112,60 @@ impl<T: 'static> Signal<T> {
     pub fn into_split(self) -> (SignalRead<T>, SignalWrite<T>) {
         (SignalRead::new(self.node), SignalWrite::new(self.node))
     }
+
+    /// Tag the signal for debugging. With the `signal_log` feature, every write is logged with the new value
+    pub fn named(self, name: &'static str) -> Self
+    where
+        T: std::fmt::Debug,
+    {
+        ReactiveStorage::map_with_downcast(&self.node, |state| {
+            state.write().unwrap().name = Some(SignalName {
+                name,
+                format: |value| format!("{value:?}"),
+            });
+        });
+
+        self
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        ReactiveStorage::try_with_downcast(&self.node, |state| {
+            state.read().ok().and_then(|state| state.name.as_ref().map(|name| name.name))
+        })
+    }
+}
+
+impl<T: 'static> Signal<Vec<T>> {
+    pub fn push(&self, item: T) {
+        self.update(|list| list.push(item));
+    }
+
+    /// # Panic
+    /// If `index` is out of bounds, like [`Vec::remove`]
+    pub fn remove(&self, index: usize) -> T {
+        self.update_returning(|list| list.remove(index))
+    }
+
+    pub fn clear(&self) {
+        self.update(Vec::clear);
+    }
+
+    /// Read the length without subscribing to the signal
+    pub fn len(&self) -> usize {
+        self.with_untracked(Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(any(test, feature = "signal_log"))]
+fn log_write(name: &str, value: &str) {
+    eprintln!("[signal] {name} = {value}");
+
+    #[cfg(test)]
+    signal_test::LOG.with_borrow_mut(|log| log.push(format!("{name} = {value}")));
 }
 
 impl<T: 'static> Reactive for Signal<T> {
@@ -202,7 +265,13 @@ impl<T: 'static> Write for Signal<T> {
 
     fn write(&self, f: impl FnOnce(&mut Self::Value)) {
         ReactiveStorage::map_with_downcast(&self.node, |state| {
-            f(&mut state.write().unwrap().value)
+            let mut state = state.write().unwrap();
+            f(&mut state.value);
+
+            #[cfg(any(test, feature = "signal_log"))]
+            if let Some(name) = state.name.as_ref() {
+                log_write(name.name, &(name.format)(&state.value));
+            }
         });
     }
 }
@@ -290,6 +359,25 @@ impl<T: 'static> std::fmt::Debug for Signal<T> {
 mod signal_test {
     use super::*;
 
+    thread_local! {
+        pub(super) static LOG: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    #[test]
+    fn named_signal_logs_writes() {
+        let count = Signal::new(0i32).named("count");
+        let unnamed = Signal::new(0i32);
+        assert_eq!(count.name(), Some("count"));
+        assert_eq!(unnamed.name(), None);
+
+        count.set(1);
+        unnamed.set(2);
+        count.update(|num| *num += 68);
+
+        let log = LOG.with_borrow_mut(std::mem::take);
+        assert_eq!(log, ["count = 1", "count = 69"]);
+    }
+
     #[test]
     fn signal() {
         let (counter, set_counter) = Signal::split(0i32);
@@ -305,6 +393,87 @@ mod signal_test {
         assert_eq!(r.unwrap().parse(), Ok(-69));
     }
 
+    #[test]
+    fn update_returning() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use crate::effect::Effect;
+
+        let (list, set_list) = Signal::split(vec![1, 2]);
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        Effect::new({
+            let runs = Arc::clone(&runs);
+            move |_| {
+                list.with(|_| {});
+                runs.fetch_add(1, Ordering::AcqRel);
+            }
+        });
+
+        let delta = std::time::Duration::from_millis(100);
+        std::thread::sleep(delta);
+        assert_eq!(runs.load(Ordering::Acquire), 1);
+
+        let len = set_list.update_returning(|list| {
+            list.push(3);
+            list.len()
+        });
+        assert_eq!(len, 3);
+
+        std::thread::sleep(delta);
+        assert_eq!(runs.load(Ordering::Acquire), 2);
+        assert_eq!(list.get_untracked(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn vec_helpers() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use crate::effect::Effect;
+
+        let list = Signal::new(Vec::<u32>::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+        let len_runs = Arc::new(AtomicUsize::new(0));
+
+        Effect::new({
+            let runs = Arc::clone(&runs);
+            move |_| {
+                list.with(|_| {});
+                runs.fetch_add(1, Ordering::AcqRel);
+            }
+        });
+
+        Effect::new({
+            let len_runs = Arc::clone(&len_runs);
+            move |_| {
+                let _ = list.len();
+                len_runs.fetch_add(1, Ordering::AcqRel);
+            }
+        });
+
+        let delta = std::time::Duration::from_millis(100);
+        std::thread::sleep(delta);
+
+        list.push(1);
+        list.push(2);
+        std::thread::sleep(delta);
+        assert_eq!(list.len(), 2);
+        assert!(runs.load(Ordering::Acquire) > 1);
+
+        let before = runs.load(Ordering::Acquire);
+        assert_eq!(list.remove(0), 1);
+        std::thread::sleep(delta);
+        assert_eq!(list.get_untracked(), [2]);
+        assert!(runs.load(Ordering::Acquire) > before);
+
+        list.clear();
+        std::thread::sleep(delta);
+        assert!(list.is_empty());
+
+        // len() doesn't subscribe
+        assert_eq!(len_runs.load(Ordering::Acquire), 1);
+    }
+
     #[test]
     fn derive() {
         let rw = Signal::new(0i32);
@@ -27,6 +27,11 @@ impl Sources {
     pub(crate) fn try_update(&self) -> bool {
         self.0.iter().any(AnySource::try_update)
     }
+
+    /// Subscribe to every source again
+    pub(crate) fn subscribe(&self, subscriber: &AnySubscriber) {
+        self.0.iter().for_each(|source| source.add_subscriber(subscriber.clone()));
+    }
 }
 
 impl AnySource {
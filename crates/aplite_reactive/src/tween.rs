@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use aplite_future::{Executor, sleep};
+use aplite_types::Color;
+
+use crate::memo::Memo;
+use crate::signal::Signal;
+use crate::reactive_traits::*;
+
+/// Values which can be interpolated by a [`Tween`]
+pub trait Lerp: Clone + PartialEq + 'static {
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        let channel = |from: u8, to: u8| (from as f32).lerp(&(to as f32), t).round() as u8;
+
+        Color::new(
+            channel(self.r, to.r),
+            channel(self.g, to.g),
+            channel(self.b, to.b),
+            channel(self.a, to.a),
+        )
+    }
+}
+
+/// Interpolates between two values over time. The clock only moves when [`advance`](Tween::advance)
+/// is called, or on every frame once [`start`](Tween::start)ed
+pub struct Tween<T> {
+    clock: Signal<Duration>,
+    duration: Duration,
+    value: Memo<T>,
+}
+
+impl<T> Clone for Tween<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Tween<T> {}
+
+impl<T: Lerp> Tween<T> {
+    /// `easing` maps the linear progress into the eased one, see [`aplite_types::easing`]
+    pub fn new(from: T, to: T, duration: Duration, easing: impl Fn(f32) -> f32 + 'static) -> Self {
+        let clock = Signal::new(Duration::ZERO);
+
+        let value = Memo::new(move |_| {
+            let elapsed = clock.get();
+            let t = if duration.is_zero() {
+                1.
+            } else {
+                (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.)
+            };
+
+            from.lerp(&to, easing(t))
+        });
+
+        Self {
+            clock,
+            duration,
+            value,
+        }
+    }
+
+    /// Move the clock forward, the value stops at the target once the duration has elapsed
+    pub fn advance(&self, delta: Duration) {
+        self.clock.update(|elapsed| *elapsed = (*elapsed + delta).min(self.duration));
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.clock.get_untracked() >= self.duration
+    }
+
+    pub fn memo(&self) -> Memo<T> {
+        self.value
+    }
+
+    /// Drive the clock from the async executor, roughly once per frame
+    pub fn start(self) -> Self {
+        const FRAME: Duration = Duration::from_millis(16);
+
+        Executor::spawn(async move {
+            while !self.is_finished() {
+                sleep(FRAME).await;
+                self.advance(FRAME);
+            }
+        });
+
+        self
+    }
+}
+
+/// Start a [`Tween`] and return the interpolated value, read it over frames to animate
+pub fn tween<T: Lerp>(
+    from: T,
+    to: T,
+    duration: Duration,
+    easing: impl Fn(f32) -> f32 + 'static,
+) -> Memo<T> {
+    Tween::new(from, to, duration, easing)
+        .start()
+        .memo()
+}
+
+#[cfg(test)]
+mod tween_test {
+    use aplite_types::rgb;
+    use aplite_types::easing::{ease_in_out_quad, linear};
+    use super::*;
+
+    #[test]
+    fn step_clock() {
+        let tween = Tween::new(0f32, 10., Duration::from_millis(100), linear);
+        let value = tween.memo();
+        assert_eq!(value.get_untracked(), 0.);
+
+        tween.advance(Duration::from_millis(25));
+        assert_eq!(value.get_untracked(), 2.5);
+
+        tween.advance(Duration::from_millis(25));
+        assert_eq!(value.get_untracked(), 5.);
+        assert!(!tween.is_finished());
+
+        tween.advance(Duration::from_millis(500));
+        assert_eq!(value.get_untracked(), 10.);
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn eased_color() {
+        let tween = Tween::new(rgb(0x000000), rgb(0xc8c8c8), Duration::from_millis(100), ease_in_out_quad);
+        let value = tween.memo();
+
+        tween.advance(Duration::from_millis(25));
+        assert_eq!(value.get_untracked(), rgb(0x191919));
+
+        tween.advance(Duration::from_millis(25));
+        assert_eq!(value.get_untracked(), rgb(0x646464));
+
+        tween.advance(Duration::from_millis(50));
+        assert_eq!(value.get_untracked(), rgb(0xc8c8c8));
+    }
+}
@@ -5,21 +5,47 @@ use rustc_hash::FxHashMap;
 use aplite_types::{Rect, Size, Point};
 use aplite_storage::{SparseTree, SlotMap, SlotId};
 
-#[derive(Debug, Clone, Copy)]
-pub(crate) struct Uv {
-    pub(crate) min_x: f32,
-    pub(crate) min_y: f32,
-    pub(crate) max_x: f32,
-    pub(crate) max_y: f32,
+/// Normalized texture coordinates of a rect inside an atlas
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uv {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
 }
 
 impl Uv {
-    pub(crate) const DEFAULT: Self = Self {
-        min_x: 0.,
-        min_y: 0.,
-        max_x: 1.,
-        max_y: 1.,
-    };
+    /// Covers the whole texture, `0.0..1.0` on both axes
+    pub const fn full() -> Self {
+        Self {
+            min_x: 0.,
+            min_y: 0.,
+            max_x: 1.,
+            max_y: 1.,
+        }
+    }
+
+    /// Normalize a rect in pixels against the size of the atlas containing it
+    pub const fn from_rect(rect: Rect, atlas_size: Size) -> Self {
+        let min_x = rect.x / atlas_size.width;
+        let min_y = rect.y / atlas_size.height;
+
+        Self {
+            min_x,
+            min_y,
+            max_x: min_x + rect.width / atlas_size.width,
+            max_y: min_y + rect.height / atlas_size.height,
+        }
+    }
+
+    pub const fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+
+    pub const fn height(&self) -> f32 {
+        self.max_y - self.min_y
+    }
 }
 
 #[derive(Clone)]
@@ -46,6 +72,10 @@ impl TextureRef {
                 bytes,
             })
     }
+
+    pub fn is_alive(&self) -> bool {
+        self.bytes.strong_count() > 0
+    }
 }
 
 impl PartialEq for TextureRef {
@@ -92,15 +122,32 @@ impl TextureData {
 
 pub(crate) struct Atlas {
     texture: wgpu::Texture,
+    label: String,
+    /// The largest texture dimension supported by the device, the atlas won't grow beyond it
+    max_dimension: f32,
 
     pub(crate) allocator: AtlasAllocator,
     pending_data: Vec<(Rect, TextureRef)>,
-    processed: FxHashMap<TextureRef, Uv>,
+    processed: FxHashMap<TextureRef, Rect>,
 }
 
 impl Atlas {
     pub(crate) fn new(device: &wgpu::Device, size: Size, label: &str) -> Self {
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
+        let max_dimension = device.limits().max_texture_dimension_2d as f32;
+        let size = Size::new(size.width.min(max_dimension), size.height.min(max_dimension));
+
+        Self {
+            texture: Self::create_texture(device, size, label),
+            label: label.to_string(),
+            max_dimension,
+            allocator: AtlasAllocator::new(size),
+            pending_data: Vec::new(),
+            processed: FxHashMap::default(),
+        }
+    }
+
+    fn create_texture(device: &wgpu::Device, size: Size, label: &str) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
             label: Some(label),
             size: wgpu::Extent3d {
                 width: size.width as u32,
@@ -115,98 +162,128 @@ impl Atlas {
                 | wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
-        });
-
-        Self {
-            allocator: AtlasAllocator::new(size),
-            texture,
-            pending_data: Vec::new(),
-            processed: FxHashMap::default(),
-        }
+        })
     }
 
     pub(crate) fn view(&self) -> wgpu::TextureView {
         self.texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 
+    /// When the data doesn't fit anymore, the atlas doubles each dimension until it does, up to the device limit.
+    /// UVs handed out before the growth are relative to the old size, whatever was drawn with them needs rescaling
     pub(crate) fn append(&mut self, data: &TextureRef) -> Option<Uv> {
-        if let Some(uv) = self.processed.get(data) {
-            return Some(*uv)
+        let pending = self.pending_data
+            .iter()
+            .find_map(|(rect, pending)| (pending == data).then_some(rect));
+
+        if let Some(rect) = self.processed.get(data).or(pending) {
+            return Some(self.allocator.get_uv(*rect))
         }
 
         let size = Size::new(data.width as _, data.height as _);
 
-        self.allocator.alloc(size).map(|rect| {
-            self.pending_data.push((rect, data.clone()));
-            self.allocator.get_uv(rect)
-        })
+        loop {
+            if let Some(rect) = self.allocator.alloc(size) {
+                self.pending_data.push((rect, data.clone()));
+                return Some(self.allocator.get_uv(rect))
+            }
+
+            let bound = self.allocator.bound.size();
+            if bound.width >= self.max_dimension && bound.height >= self.max_dimension {
+                return None
+            }
+
+            self.allocator.grow(Size::new(
+                (bound.width * 2.).min(self.max_dimension),
+                (bound.height * 2.).min(self.max_dimension),
+            ));
+        }
     }
 
-    pub(crate) fn update(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
+    /// Upload the pending data. Returns true if the texture has been recreated with a bigger size,
+    /// any bind group using the previous [`view`](Self::view) needs to be recreated
+    pub(crate) fn update(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) -> bool {
         use wgpu::util::DeviceExt;
         use wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as ALIGN;
 
+        let size = self.allocator.bound.size();
+        let prev = self.texture.size();
+        let grown = size.width as u32 != prev.width || size.height as u32 != prev.height;
+
+        if grown {
+            let texture = Self::create_texture(device, size, &self.label);
+            encoder.copy_texture_to_texture(
+                self.texture.as_image_copy(),
+                texture.as_image_copy(),
+                prev,
+            );
+            self.texture = texture;
+        }
+
         if !self.pending_data.is_empty() {
             std::mem::take(&mut self.pending_data)
                 .into_iter()
-                .for_each(|(rect, pending_data)| {
-                    if let Some(data) = pending_data.upgrade() {
-                        let width = data.width * 4;
-                        let padding = (ALIGN - width % ALIGN) % ALIGN;
-                        let padded_width = width + padding;
-
-                        let mut padded_data = Vec::with_capacity((padded_width * data.height) as usize);
-
-                        let mut i = 0;
-
-                        for _ in 0..data.height {
-                            for _ in 0..data.width {
-                                padded_data.push(data.bytes[i]);
-                                i += 1;
-                            }
-                            while (padded_data.len() % ALIGN as usize) != 0 {
-                                padded_data.push(0);
-                            }
-                        }
+                .filter_map(upgrade_pending)
+                .for_each(|(rect, pending_data, data)| {
+                    let width = data.width * 4;
+                    let padding = (ALIGN - width % ALIGN) % ALIGN;
+                    let padded_width = width + padding;
+
+                    let mut padded_data = Vec::with_capacity((padded_width * data.height) as usize);
 
-                        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                            label: None,
-                            contents: &padded_data,
-                            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::MAP_WRITE,
-                        });
-
-                        encoder.copy_buffer_to_texture(
-                            wgpu::TexelCopyBufferInfo {
-                                buffer: &buffer,
-                                layout: wgpu::TexelCopyBufferLayout {
-                                    offset: 0,
-                                    bytes_per_row: Some(padded_width),
-                                    rows_per_image: None,
-                                },
+                    for row in data.bytes.chunks_exact(width as usize) {
+                        padded_data.extend_from_slice(row);
+                        padded_data.resize(padded_data.len() + padding as usize, 0);
+                    }
+
+                    let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: None,
+                        contents: &padded_data,
+                        usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::MAP_WRITE,
+                    });
+
+                    encoder.copy_buffer_to_texture(
+                        wgpu::TexelCopyBufferInfo {
+                            buffer: &buffer,
+                            layout: wgpu::TexelCopyBufferLayout {
+                                offset: 0,
+                                bytes_per_row: Some(padded_width),
+                                rows_per_image: None,
                             },
-                            wgpu::TexelCopyTextureInfo {
-                                texture: &self.texture,
-                                aspect: wgpu::TextureAspect::All,
-                                mip_level: 0,
-                                origin: wgpu::Origin3d {
-                                    x: rect.x as u32,
-                                    y: rect.y as u32,
-                                    z: 0,
-                                },
+                        },
+                        wgpu::TexelCopyTextureInfo {
+                            texture: &self.texture,
+                            aspect: wgpu::TextureAspect::All,
+                            mip_level: 0,
+                            origin: wgpu::Origin3d {
+                                x: rect.x as u32,
+                                y: rect.y as u32,
+                                z: 0,
                             },
-                            wgpu::Extent3d {
-                                width: data.width,
-                                height: data.height,
-                                depth_or_array_layers: 1,
-                            }
-                        );
-
-                        let uv = self.allocator.get_uv(rect);
+                        },
+                        wgpu::Extent3d {
+                            width: data.width,
+                            height: data.height,
+                            depth_or_array_layers: 1,
+                        }
+                    );
 
-                        self.processed.insert(pending_data, uv);
-                    }
+                    self.processed.insert(pending_data, rect);
                 });
         }
+
+        grown
+    }
+}
+
+/// Pending data whose source has been dropped before the atlas gets updated will be skipped
+fn upgrade_pending((rect, pending_data): (Rect, TextureRef)) -> Option<(Rect, TextureRef, TextureData)> {
+    match pending_data.upgrade() {
+        Some(data) => Some((rect, pending_data, data)),
+        None => {
+            eprintln!("[atlas] skipping pending texture at {rect:?}: source data has been dropped");
+            None
+        },
     }
 }
 
@@ -214,20 +291,19 @@ impl Atlas {
 // ↓ →
 
 /// The priority is to fill the atlas horizontally first from each root.
-/// The root will be placed on the left-most and stacked vertically.
-/// Once a root is assigned, horizontally is children, vertically is siblings.
+/// The root will be placed on the left-most and stacked vertically, each root sets the height of its row.
+/// Once a root is assigned, horizontally is children, vertically is the children of those children.
 /// # graphical representation
-/// ┌───────────┬───────────────────────┐
-/// │           →      first child      → child of first child
-/// │   Root0   ├───────────↓───────────┤
-/// │           │siblings of first child│
-/// ├─────↓─────┼───────────────────────┤
-/// │           │                       │
-/// │   Root1   →                       │
-/// │           │                       │
-/// └───────────┴───────────────────────┘
-/// The first child will set the boundary for it's siblings if any, and the children of it's siblings.
-/// This means the total width of the first child's siblings + their childrens <= first child's rect
+/// ┌───────────┬───────────────┬───────────────┐
+/// │           │  first child  →  next child   │
+/// │   Root0   ├───────↓───────┼───────↓───────┤
+/// │           │ stacked child │ stacked child │
+/// ├─────↓─────┼───────────────┴───────────────┤
+/// │           │                               │
+/// │   Root1   →                               │
+/// │           │                               │
+/// └───────────┴───────────────────────────────┘
+/// Each child of a root sets the width for the children stacked below it
 pub(crate) struct AtlasAllocator {
     pub(crate) bound: Rect,
     last_root: Option<SlotId>,
@@ -246,136 +322,82 @@ impl AtlasAllocator {
     }
 
     pub(crate) fn alloc(&mut self, new_size: Size) -> Option<Rect> {
-        // TODO: double the size
+        let rect = self.alloc_inner(new_size);
+
+        #[cfg(debug_assertions)] self.assert_no_overlap();
+
+        rect
+    }
+
+    fn alloc_inner(&mut self, new_size: Size) -> Option<Rect> {
         if new_size.area() > self.calculate_available_area() { return None };
 
-        match self.last_root {
-            Some(last_root) => {
-                if let Some((parent, pos)) = self.scan(new_size) {
-                    let rect = Rect::from_point_size(pos, new_size);
-
-                    self.allocated.try_insert(rect).ok().map(|id| {
-                        self.tree.insert_with_parent(id, parent);
-                        rect
-                    })
-
-                } else {
-                    // inserting as the next root
-                    let next_y = unsafe {
-                        self.allocated.get_unchecked(&last_root).max_y()
-                    };
-
-                    let pos = Point::new(0.0, next_y);
-                    let rect = Rect::from_point_size(pos, new_size);
-
-                    self.allocated.try_insert(rect).ok().map(|id| {
-                        self.tree.insert_as_root(id);
-                        self.last_root = Some(id);
-                        rect
-                    })
-                }
-            },
-            None => {
-                // first insert
-                let rect = Rect::from_size(new_size);
-
-                self.allocated.try_insert(rect).ok().map(|id| {
-                    self.tree.insert_as_root(id);
-                    self.last_root = Some(id);
-                    rect
-                })
-            },
+        if let Some((parent, pos)) = self.last_root.and_then(|_| self.scan(new_size)) {
+            let rect = Rect::from_point_size(pos, new_size);
+
+            return self.allocated.try_insert(rect).ok().map(|id| {
+                self.tree.insert_with_parent(id, parent);
+                rect
+            })
+        }
+
+        // inserting as the next root, below the last one
+        let next_y = self.last_root
+            .map(|last_root| unsafe { self.allocated.get_unchecked(&last_root).max_y() })
+            .unwrap_or_default();
+
+        let rect = Rect::from_point_size(Point::new(0.0, next_y), new_size);
+
+        if rect.max_x() > self.bound.max_x() || rect.max_y() > self.bound.max_y() {
+            return None
         }
+
+        self.allocated.try_insert(rect).ok().map(|id| {
+            self.tree.insert_as_root(id);
+            self.last_root = Some(id);
+            rect
+        })
     }
 
     /// scan each roots and try to find available position within the identified root
     fn scan(&self, new_size: Size) -> Option<(SlotId, Point)> {
         self.tree.roots().find_map(|root| unsafe {
-            let rect = self.allocated.get_unchecked(&root);
-            self.identify_member(root, rect, new_size)
+            let root_rect = self.allocated.get_unchecked(&root);
+            self.identify_member(root, root_rect, new_size)
         })
     }
 
+    /// The root sets the height of its row. First try stacking below each child of the root,
+    /// then try placing a new child on the right of the last one
     fn identify_member(
         &self,
         root: SlotId,
         root_rect: &Rect,
         new_size: Size,
     ) -> Option<(SlotId, Point)> {
-        if let Some(first) = self.tree.get_first_child(root) {
-            // the first rect will set the boundary of it's siblings if any
-            let first_rect = unsafe { self.allocated.get_unchecked(&first) };
+        let mut last_column = root_rect;
 
-            let mut current = first;
+        for column in self.tree.iter_children(root) {
+            let column_rect = unsafe { self.allocated.get_unchecked(&column) };
 
-            while let Some(sibling) = self.tree.get_next_sibling(current) {
-                let find = self.indentify_next_sibling(sibling, first_rect, new_size);
-                if find.is_some() { return find }
-                current = sibling;
-            }
+            let bottom = self.tree
+                .get_last_child(column)
+                .map(|last| unsafe { self.allocated.get_unchecked(&last).max_y() })
+                .unwrap_or(column_rect.max_y());
 
-            // assigning as the next sibling / child of the first child
-            let last_rect = unsafe { self.allocated.get_unchecked(&current) };
-
-            if new_size.width <= first_rect.width
-                && new_size.height + last_rect.max_y() <= root_rect.height
-            {
-                Some((root, Point::new(last_rect.x, last_rect.max_y())))
-            } else if new_size.width + first_rect.max_x() <= self.bound.width
-                && new_size.height <= root_rect.height
+            if new_size.width <= column_rect.width
+                && bottom + new_size.height <= root_rect.max_y()
             {
-                if let Some(lc) = self.tree.get_last_child(first) {
-                    let lr = unsafe { self.allocated.get_unchecked(&lc) };
-                    let mut curr = lc;
-                    while let Some(sib) = self.tree.get_next_sibling(curr) {
-                        let find = self.indentify_next_sibling(sib, lr, new_size);
-                        if find.is_some() { return find }
-                        curr = sib;
-                    }
-                    self.identify_member(curr, lr, new_size)
-                } else {
-                    Some((first, Point::new(first_rect.max_x(), root_rect.y)))
-                }
-            } else {
-                None
+                return Some((column, Point::new(column_rect.x, bottom)))
             }
-        } else {
-            // assign as the first child of a root if fit
-            if new_size.width + root_rect.max_x() <= self.bound.width
-                && root_rect.height <= new_size.height
-            {
-                Some((root, Point::new(root_rect.max_x(), root_rect.y)))
-            } else {
-                None
-            }
-        }
-    }
-
-    fn indentify_next_sibling(
-        &self,
-        current: SlotId,
-        first_rect_bound: &Rect,
-        new_size: Size,
-    ) -> Option<(SlotId, Point)> {
-        let current_rect = unsafe { self.allocated.get_unchecked(&current) };
-
-        if let Some(first_child) = self.tree.get_first_child(current) {
-            let first_child_rect = unsafe { self.allocated.get_unchecked(&first_child) };
 
-            let mut curr = first_child;
-
-            while let Some(next) = self.tree.get_next_sibling(curr) {
-                let find = self.indentify_next_sibling(next, first_child_rect, new_size);
-                if find.is_some() { return find }
-                curr = next;
-            }
+            last_column = column_rect;
         }
 
-        let cond1 = new_size.width + current_rect.max_x() <= first_rect_bound.max_x();
-        let cond2 = new_size.height <= current_rect.height;
-
-        if cond1 && cond2 {
-            Some((current, Point::new(current_rect.max_x(), current_rect.y)))
+        if last_column.max_x() + new_size.width <= self.bound.max_x()
+            && new_size.height <= root_rect.height
+        {
+            Some((root, Point::new(last_column.max_x(), root_rect.y)))
         } else {
             None
         }
@@ -392,6 +414,33 @@ impl AtlasAllocator {
     //     self.allocated.remove(&id)
     // }
 
+    /// Invariant of the allocator: every allocated rect lies within the bound, and no two of them overlap
+    #[cfg(any(test, debug_assertions))]
+    pub(crate) fn assert_no_overlap(&self) {
+        let overlap = |a: &Rect, b: &Rect| {
+            a.x < b.max_x() && b.x < a.max_x() && a.y < b.max_y() && b.y < a.max_y()
+        };
+
+        let allocated = self.allocated.iter().collect::<Vec<_>>();
+
+        for (i, (id, rect)) in allocated.iter().enumerate() {
+            assert!(
+                rect.x >= self.bound.x
+                    && rect.y >= self.bound.y
+                    && rect.max_x() <= self.bound.max_x()
+                    && rect.max_y() <= self.bound.max_y(),
+                "[atlas] {id:?} {rect:?} is out of bound {:?}", self.bound
+            );
+
+            for (other_id, other) in &allocated[i + 1..] {
+                assert!(
+                    !overlap(rect, other),
+                    "[atlas] {id:?} {rect:?} overlaps {other_id:?} {other:?}"
+                );
+            }
+        }
+    }
+
     fn calculate_available_area(&self) -> f32 {
         let allocated = self.allocated.iter()
             .fold(0.0,|sum, (_, rect)| {
@@ -401,18 +450,18 @@ impl AtlasAllocator {
         self.bound.area() - allocated
     }
 
-    pub(crate) fn get_uv(&self, rect: Rect) -> Uv {
-        let min_x = rect.x / self.bound.width;
-        let min_y = rect.y / self.bound.height;
-        let max_x = min_x + rect.width / self.bound.width;
-        let max_y = min_y + rect.height / self.bound.height;
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.allocated.len()
+    }
 
-        Uv {
-            min_x,
-            min_y,
-            max_x,
-            max_y,
-        }
+    /// Extend the bound, already allocated rects keep their position
+    pub(crate) fn grow(&mut self, size: Size) {
+        self.bound.set_size(size);
+    }
+
+    pub(crate) fn get_uv(&self, rect: Rect) -> Uv {
+        Uv::from_rect(rect, self.bound.size())
     }
 }
 
@@ -459,14 +508,102 @@ mod atlas_test {
 
         eprintln!("{:#?}", allocator.tree);
 
-        // > EntityId(0)
-        //   ├─ EntityId(2)
-        //   ├─ EntityId(3)
-        //   └─ EntityId(4)
-        //      ├─ EntityId(6)
-        //      └─ EntityId(7)
-        //         └─ EntityId(8)
-        // > EntityId(1)
-        // > EntityId(5)
+        // > SlotId(0)
+        //   └─ SlotId(2)
+        //      ├─ SlotId(3)
+        //      └─ SlotId(4)
+        // > SlotId(1)
+        //   └─ SlotId(6)
+        //      ├─ SlotId(7)
+        //      └─ SlotId(8)
+        // > SlotId(5)
+    }
+
+    #[test]
+    fn random_allocations_never_overlap() {
+        // xorshift, so the sequence is reproducible
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |max: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            1 + state % max
+        };
+
+        for _ in 0..20 {
+            let mut allocator = AtlasAllocator::new((1000, 1000));
+
+            for _ in 0..200 {
+                let size = Size::new(next(200) as f32, next(200) as f32);
+                allocator.alloc(size);
+            }
+
+            allocator.assert_no_overlap();
+        }
+    }
+
+    #[test]
+    fn uv_from_rect() {
+        let atlas_size = Size::new(400., 200.);
+        let uv = Uv::from_rect(Rect::new(100., 50., 200., 100.), atlas_size);
+
+        assert_eq!(uv, Uv { min_x: 0.25, min_y: 0.25, max_x: 0.75, max_y: 0.75 });
+        assert_eq!((uv.width(), uv.height()), (0.5, 0.5));
+
+        let uv = Uv::from_rect(Rect::new(0., 0., 400., 200.), atlas_size);
+        assert_eq!(uv, Uv::full());
+        assert_eq!((uv.width(), uv.height()), (1., 1.));
+    }
+
+    #[test]
+    fn uv_after_grow() {
+        let mut allocator = AtlasAllocator::new((100, 100));
+        let first = allocator.alloc(Size::new(100., 50.)).unwrap();
+        let second = allocator.alloc(Size::new(100., 50.)).unwrap();
+        assert!(allocator.alloc(Size::new(50., 50.)).is_none());
+
+        let uv = allocator.get_uv(second);
+        assert_eq!((uv.min_x, uv.min_y, uv.max_x, uv.max_y), (0., 0.5, 1., 1.));
+
+        allocator.grow(Size::new(200., 200.));
+
+        // same rects, recomputed against the new bound
+        let uv = allocator.get_uv(first);
+        assert_eq!((uv.min_x, uv.min_y, uv.max_x, uv.max_y), (0., 0., 0.5, 0.25));
+        let uv = allocator.get_uv(second);
+        assert_eq!((uv.min_x, uv.min_y, uv.max_x, uv.max_y), (0., 0.25, 0.5, 0.5));
+
+        let third = allocator.alloc(Size::new(50., 50.)).unwrap();
+        assert!(third.max_x() <= 200. && third.max_y() <= 200.);
+        allocator.assert_no_overlap();
+    }
+
+    #[test]
+    fn skip_dropped_pending_data() {
+        use aplite_types::ImageData;
+
+        let alive = ImageData::new((1, 1), &[0, 0, 0, 255]);
+        let dropped = ImageData::new((1, 1), &[255, 255, 255, 255]);
+
+        let alive_ref = alive.downgrade();
+        let dropped_ref = dropped.downgrade();
+        assert_eq!(dropped_ref.strong_count(), 1);
+
+        let pending = [
+            (Rect::new(0., 0., 1., 1.), TextureRef::new(1, 1, alive_ref.bytes.clone())),
+            (Rect::new(1., 0., 1., 1.), TextureRef::new(1, 1, dropped_ref.bytes.clone())),
+        ];
+
+        drop(dropped);
+        assert!(alive_ref.is_alive());
+        assert!(!dropped_ref.is_alive());
+
+        let uploaded = pending
+            .into_iter()
+            .filter_map(upgrade_pending)
+            .map(|(rect, _, _)| rect)
+            .collect::<Vec<_>>();
+
+        assert_eq!(uploaded, vec![Rect::new(0., 0., 1., 1.)]);
     }
 }
@@ -25,6 +25,13 @@ impl<T> Buffer<T> {
         }
     }
 
+    /// How many `T` fit in the buffer before it has to grow
+    pub(crate) fn capacity(&self) -> u64 {
+        self.buffer.size() / size_of::<T>() as u64
+    }
+
+    /// Write `data` at `offset`, counted in `T`. The buffer doubles its capacity until the data fits,
+    /// returns `true` when it did, since any bind group or bundle holding the old buffer is stale
     pub(crate) fn write(
         &mut self,
         device: &wgpu::Device,
@@ -32,13 +39,18 @@ impl<T> Buffer<T> {
         offset: u64,
         data: &[T],
     ) -> bool {
-        let current_size = self.buffer.size();
-        let realloc = (offset + data.len() as u64) * size_of::<T>() as u64 > current_size;
+        let required = offset + data.len() as u64;
+        let realloc = required > self.capacity();
 
         if realloc {
+            let mut capacity = self.capacity().max(1);
+            while capacity < required {
+                capacity *= 2;
+            }
+
             let new_buffer = device.create_buffer(&wgpu::BufferDescriptor {
                 label: Some(std::any::type_name::<T>()),
-                size: current_size * 2,
+                size: capacity * size_of::<T>() as u64,
                 usage: self.buffer.usage(),
                 mapped_at_creation: false,
             });
@@ -50,9 +62,10 @@ impl<T> Buffer<T> {
             );
 
             resize_encoder.copy_buffer_to_buffer(&self.buffer, 0, &new_buffer, 0, None);
-            resize_encoder.finish();
 
-            self.buffer.destroy();
+            // flushes the pending writes into the old buffer before copying them over
+            queue.submit([resize_encoder.finish()]);
+
             self.buffer = new_buffer;
         }
 
@@ -95,7 +108,7 @@ impl<T> Buffer<T> {
     }
 }
 
-fn cast_slice<SRC: Sized, DST: Sized>(src: &[SRC]) -> &[DST] {
+pub(crate) fn cast_slice<SRC: Sized, DST: Sized>(src: &[SRC]) -> &[DST] {
     let len = size_of_val(src);
     unsafe { core::slice::from_raw_parts(src.as_ptr() as *const DST, len) }
 }
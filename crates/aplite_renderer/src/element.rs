@@ -1,8 +1,14 @@
-use aplite_types::{CornerRadius, Size};
+use aplite_types::{Circle, Color, CornerRadius, Point, Rect, RoundedRect, Size, Vec2f};
 use aplite_types::theme::basic;
 
+use crate::atlas::Uv;
+
+/// Mirrors the `Element` struct in the shader, which reads it from a storage buffer.
+/// Every field is 4 bytes aligned, so the 72 bytes have no padding, in declaration order:
+/// size (2 x f32), background, border, corners, shape (u32), border width, opacity (f32),
+/// border texture (u32), border uv (4 x f32), border style (u32), border dash & gap (2 x f32)
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Element {
     pub(crate) size: Size,
     pub(crate) background: u32,
@@ -10,18 +16,64 @@ pub struct Element {
     pub(crate) corners: u32,
     pub(crate) shape: u32,
     pub(crate) border_width: f32,
+    pub(crate) opacity: f32,
+    /// 0 paints the border with the packed color, 1 samples it from the atlas at `border_uv`
+    pub(crate) border_texture: u32,
+    pub(crate) border_uv: [f32; 4],
+    /// [`BorderStyle::id`], with the dash & gap lengths normalized per axis like the size
+    pub(crate) border_style: u32,
+    pub(crate) border_dash: [f32; 2],
+    pub(crate) border_gap: [f32; 2],
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Shape {
-    Circle = 0,
-    Rect = 1,
-    RoundedRect = 2,
-    Triangle = 3,
-    Text = 4,
+    Circle,
+    Rect,
+    RoundedRect,
+    Triangle,
+    Text,
+    /// Convex polygon, the points are relative to the top-left of the drawn rect
+    Polygon(Vec<Point>),
+}
+
+impl Shape {
+    /// The shape id as read by the shader
+    pub const fn id(&self) -> u32 {
+        match self {
+            Self::Circle => 0,
+            Self::Rect => 1,
+            Self::RoundedRect => 2,
+            Self::Triangle => 3,
+            Self::Text => 4,
+            Self::Polygon(_) => 5,
+        }
+    }
+}
+
+/// Stroke pattern of the border, the lengths are in logical pixels along the border
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BorderStyle {
+    #[default]
+    Solid,
+    Dashed { dash: f32, gap: f32 },
+    /// Dots as long as the border is wide
+    Dotted,
+}
+
+impl BorderStyle {
+    /// The border style id as read by the shader
+    pub const fn id(&self) -> u32 {
+        match self {
+            Self::Solid => 0,
+            Self::Dashed { .. } => 1,
+            Self::Dotted => 2,
+        }
+    }
 }
 
+const _: () = assert!(size_of::<Element>() == 72);
+
 impl Element {
     pub fn new(size: Size) -> Self {
         Self {
@@ -31,6 +83,12 @@ impl Element {
             corners: 0,
             shape: 1,
             border_width: 0.0,
+            opacity: 1.0,
+            border_texture: 0,
+            border_uv: [0.0; 4],
+            border_style: 0,
+            border_dash: [0.0; 2],
+            border_gap: [0.0; 2],
         }
     }
 
@@ -39,13 +97,173 @@ impl Element {
         self
     }
 
-    pub(crate) fn with_border_width(mut self, val: f32) -> Self {
-        self.border_width = val;
+    /// See [`set_stroke_width`](Self::set_stroke_width)
+    pub(crate) fn with_border_width(mut self, width: f32, screen: &Size) -> Self {
+        self.set_stroke_width(width, screen);
+        self
+    }
+
+    /// Overall opacity multiplied into the final fragment alpha, clamped to `0.0..=1.0`
+    pub(crate) fn with_opacity(mut self, val: f32) -> Self {
+        self.opacity = val.clamp(0.0, 1.0);
+        self
+    }
+
+    pub(crate) fn with_border_image(mut self, uv: Uv) -> Self {
+        self.border_texture = 1;
+        self.border_uv = [uv.min_x, uv.min_y, uv.max_x, uv.max_y];
+        self
+    }
+
+    /// Scale the border uv per axis, to follow the atlas when it grows
+    pub(crate) fn scale_border_uv(&mut self, scale: Size) {
+        let [min_x, min_y, max_x, max_y] = self.border_uv;
+        self.border_uv = [min_x * scale.width, min_y * scale.height, max_x * scale.width, max_y * scale.height];
+    }
+
+    /// The pattern lengths are normalized against the `screen` per axis, the shader picks the axis along the edge
+    pub(crate) fn with_border_style(mut self, style: &BorderStyle, screen: &Size) -> Self {
+        let (dash, gap) = match style {
+            BorderStyle::Solid => (0.0, 0.0),
+            BorderStyle::Dashed { dash, gap } => (*dash, *gap),
+            BorderStyle::Dotted => {
+                let width = self.stroke_width(screen);
+                (width, width)
+            },
+        };
+
+        self.border_style = style.id();
+        self.border_dash = [dash / screen.width, dash / screen.height];
+        self.border_gap = [gap / screen.width, gap / screen.height];
         self
     }
 
-    pub(crate) fn with_shape(mut self, shape: Shape) -> Self {
-        self.shape = shape as u32;
+    pub(crate) fn with_shape(mut self, shape: &Shape) -> Self {
+        self.shape = shape.id();
         self
     }
+
+    pub fn set_fill_color(&mut self, color: Color) {
+        self.background = color.pack_u32();
+    }
+
+    pub fn set_stroke_color(&mut self, color: Color) {
+        self.border = color.pack_u32();
+    }
+
+    /// `width` is in logical pixels, and gets normalized against the `screen` width like the size
+    pub fn set_stroke_width(&mut self, width: f32, screen: &Size) {
+        self.border_width = width / screen.width;
+    }
+
+    pub fn set_corners(&mut self, corner_radius: CornerRadius) {
+        self.corners = corner_radius.pack_u32();
+    }
+
+    pub fn fill_color(&self) -> Color {
+        Color::unpack(self.background)
+    }
+
+    pub fn stroke_color(&self) -> Color {
+        Color::unpack(self.border)
+    }
+
+    /// The border width in logical pixels, see [`set_stroke_width`](Self::set_stroke_width)
+    pub fn stroke_width(&self, screen: &Size) -> f32 {
+        self.border_width * screen.width
+    }
+
+    pub fn corners(&self) -> CornerRadius {
+        CornerRadius::unpack(self.corners)
+    }
+
+    /// The bytes as uploaded into the storage buffer
+    pub fn as_bytes(&self) -> &[u8] {
+        crate::buffer::cast_slice(std::slice::from_ref(self))
+    }
+
+    /// The inverse of [`as_bytes`](Self::as_bytes), [`None`] if `bytes` isn't exactly one element long
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != size_of::<Self>() { return None }
+
+        // every field is a plain u32 or f32, any bit pattern is valid
+        Some(unsafe { bytes.as_ptr().cast::<Self>().read_unaligned() })
+    }
+
+    /// `local_point` is relative to the top-left of the element, in the same unit as its size.
+    /// Circles & rounded rects exclude the points outside of their curves, other shapes use the bounding rect
+    pub fn hit_test(&self, local_point: Vec2f) -> bool {
+        let rect = Rect::from_size(self.size);
+
+        if self.shape == Shape::Circle.id() {
+            let radius = self.size.width / 2.;
+            Circle::new(Vec2f::new(radius, radius), radius).contains_point(local_point)
+        } else if self.shape == Shape::RoundedRect.id() {
+            RoundedRect::from_rect_radius(rect, self.corners()).contains_point(local_point)
+        } else {
+            rect.contains(&local_point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod element_test {
+    use aplite_types::rgba;
+    use super::*;
+
+    #[test]
+    fn setters_round_trip() {
+        let mut element = Element::new(Size::new(10., 10.));
+
+        element.set_fill_color(rgba(0x11223344));
+        element.set_stroke_color(rgba(0xaabbccdd));
+        element.set_stroke_width(2.5, &Size::new(800., 600.));
+        element.set_corners(CornerRadius::new(1, 2, 3, 4));
+
+        assert_eq!(element.fill_color(), rgba(0x11223344));
+        assert_eq!(element.stroke_color(), rgba(0xaabbccdd));
+        assert_eq!(element.stroke_width(&Size::new(800., 600.)), 2.5);
+        assert_eq!(element.corners(), CornerRadius::new(1, 2, 3, 4));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let element = Element::new(Size::new(0.5, 0.25))
+            .with_shape(&Shape::RoundedRect)
+            .with_corner_radius(&CornerRadius::new(1, 2, 3, 4))
+            .with_border_width(2., &Size::new(2., 4.))
+            .with_opacity(0.5)
+            .with_border_style(&BorderStyle::Dashed { dash: 8., gap: 4. }, &Size::new(2., 4.));
+
+        let bytes = element.as_bytes();
+        assert_eq!(bytes.len(), size_of::<Element>());
+        assert_eq!(bytes.len(), 72);
+        assert_eq!(&bytes[..4], 0.5f32.to_ne_bytes());
+
+        assert_eq!(Element::from_bytes(bytes), Some(element));
+        assert_eq!(Element::from_bytes(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn hit_test_shapes() {
+        let circle = Element::new(Size::new(100., 100.)).with_shape(&Shape::Circle);
+        assert!(circle.hit_test(Vec2f::new(50., 50.)));
+        assert!(circle.hit_test(Vec2f::new(50., 1.)));
+        assert!(!circle.hit_test(Vec2f::new(5., 5.)));
+        assert!(!circle.hit_test(Vec2f::new(95., 95.)));
+
+        // radius of 20 on every corner
+        let rounded = Element::new(Size::new(100., 100.))
+            .with_shape(&Shape::RoundedRect)
+            .with_corner_radius(&CornerRadius::splat(80));
+        assert!(rounded.hit_test(Vec2f::new(8., 8.)));
+        assert!(!rounded.hit_test(Vec2f::new(4., 4.)));
+        assert!(!rounded.hit_test(Vec2f::new(1., 1.)));
+        assert!(!rounded.hit_test(Vec2f::new(99., 1.)));
+        assert!(rounded.hit_test(Vec2f::new(99., 50.)));
+
+        let rect = Element::new(Size::new(100., 100.)).with_shape(&Shape::Rect);
+        assert!(rect.hit_test(Vec2f::new(1., 1.)));
+        assert!(!rect.hit_test(Vec2f::new(101., 1.)));
+    }
 }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use fontdue::layout::{Layout, LayoutSettings, CoordinateSystem, TextStyle};
 use fontdue::{Font, FontSettings};
@@ -9,6 +9,34 @@ use crate::atlas::{Atlas, Uv, TextureRef};
 
 const DEFAULT_FONT: &[u8] = include_bytes!("../../../resources/JetBrainsMonoNerdFont-Regular.ttf");
 
+fn font_settings() -> FontSettings {
+    FontSettings {
+        collection_index: 0,
+        scale: 100.,
+        load_substitutions: true,
+    }
+}
+
+fn text_size(layout: &Layout) -> Size {
+    let width = layout
+        .glyphs()
+        .iter()
+        .fold(0f32, |width, glyph| width.max(glyph.x + glyph.width as f32));
+
+    Size::new(width, layout.height())
+}
+
+/// Measure the logical size of the text laid out with the default font, usable before any renderer exists
+pub fn measure_text(text: &str, font_size: f32) -> Size {
+    static FONT: OnceLock<Font> = OnceLock::new();
+
+    let font = FONT.get_or_init(|| Font::from_bytes(DEFAULT_FONT, font_settings()).unwrap());
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&[font], &TextStyle::new(text, font_size, 0));
+
+    text_size(&layout)
+}
+
 #[derive(Debug, Clone, Copy)]
 struct Char {
     c: char,
@@ -77,13 +105,7 @@ pub(crate) struct FontHandler {
 
 impl FontHandler {
     pub(crate) fn new(device: &wgpu::Device, size: Size) -> Self {
-        let settings = FontSettings {
-            collection_index: 0,
-            scale: 100.,
-            load_substitutions: true,
-        };
-
-        let font = Font::from_bytes(DEFAULT_FONT, settings).unwrap();
+        let font = Font::from_bytes(DEFAULT_FONT, font_settings()).unwrap();
         let layout = Layout::new(CoordinateSystem::PositiveYDown);
         let glyphs = FxHashMap::default();
         let atlas = Atlas::new(device, size, "glyph");
@@ -100,8 +122,9 @@ impl FontHandler {
         self.atlas.view()
     }
 
-    pub(crate) fn update(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
-        self.atlas.update(device, encoder);
+    /// Returns true if the atlas texture has been recreated, see [`Atlas::update`]
+    pub(crate) fn update(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) -> bool {
+        self.atlas.update(device, encoder)
     }
 
     pub(crate) fn setup(&mut self, text: &str, size: f32, scale: f32, rect: &Rect) {
@@ -117,6 +140,12 @@ impl FontHandler {
         self.layout.append(&[&self.font], &TextStyle::new(text, px, 0));
     }
 
+    /// The logical size of the laid out text, without rasterizing the glyphs
+    pub(crate) fn measure(&mut self, text: &str, font_size: f32) -> Size {
+        self.setup(text, font_size, 1., &Rect::default());
+        text_size(&self.layout)
+    }
+
     pub(crate) fn rasterize_text(
         &mut self,
         text: &str,
@@ -180,3 +209,45 @@ impl FontHandler {
         prims
     }
 }
+
+#[cfg(test)]
+mod glyph_test {
+    use super::*;
+    use crate::renderer::device;
+
+    #[test]
+    fn rasterize_into_atlas() {
+        let Some((device, _)) = device() else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let mut handler = FontHandler::new(&device, Size::square(1024.));
+        let rect = Rect::new(0., 0., 200., 50.);
+        let quads = handler.rasterize_text("hello", 20., 1., &rect);
+
+        assert_eq!(quads.len(), 5);
+        assert_eq!(handler.glyphs.len(), 4);
+
+        // cached glyphs are reused on the next frame
+        let quads = handler.rasterize_text("hole", 20., 1., &rect);
+        assert_eq!(quads.len(), 4);
+        assert_eq!(handler.glyphs.len(), 4);
+    }
+
+    #[test]
+    fn measure_grows_with_text() {
+        let Some((device, _)) = device() else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let mut handler = FontHandler::new(&device, Size::square(1024.));
+        let short = handler.measure("ab", 20.);
+        let long = handler.measure("abcd", 20.);
+
+        assert!(short.width > 0.);
+        assert!(long.width > short.width);
+        assert_eq!(long.height, short.height);
+    }
+}
@@ -9,10 +9,11 @@ mod shader;
 mod storage;
 mod util;
 
-pub use renderer::{Renderer, Scene, DrawArgs};
-pub use element::{Element, Shape};
-pub use mesh::Vertices;
-pub use atlas::{TextureRef, TextureData};
+pub use renderer::{Renderer, RendererCaps, RendererConfig, Scene, DrawArgs};
+pub use element::{BorderStyle, Element, Shape};
+pub use mesh::{Vertices, Vertex};
+pub use atlas::{TextureRef, TextureData, Uv};
+pub use glyph::measure_text;
 
 #[derive(Debug)]
 pub enum InitiationError {
@@ -30,6 +31,13 @@ pub enum RenderError {
     PollError,
 }
 
+impl RenderError {
+    /// Whether the frame can be rendered again after reconfiguring the surface
+    pub const fn is_recoverable(&self) -> bool {
+        matches!(self, Self::ShouldResize | Self::TimeOut)
+    }
+}
+
 impl std::fmt::Display for RenderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!{f, "{self:?}"}
@@ -79,3 +87,22 @@ impl From<wgpu::PollError> for RenderError {
         Self::PollError
     }
 }
+
+#[cfg(test)]
+mod error_test {
+    use super::*;
+
+    #[test]
+    fn recoverable_errors() {
+        assert!(RenderError::ShouldResize.is_recoverable());
+        assert!(RenderError::TimeOut.is_recoverable());
+
+        assert!(!RenderError::ShouldExit.is_recoverable());
+        assert!(!RenderError::TextureAcquiringFailed.is_recoverable());
+        assert!(!RenderError::PollError.is_recoverable());
+
+        assert!(RenderError::from(wgpu::SurfaceError::Outdated).is_recoverable());
+        assert!(RenderError::from(wgpu::SurfaceError::Lost).is_recoverable());
+        assert!(!RenderError::from(wgpu::SurfaceError::OutOfMemory).is_recoverable());
+    }
+}
@@ -1,4 +1,4 @@
-use aplite_types::{Rect, Size, Vec2f};
+use aplite_types::{Matrix3x2, Point, Rect, Size, Vec2f};
 
 use crate::buffer::Buffer;
 use crate::atlas::Uv;
@@ -7,6 +7,8 @@ pub(crate) struct MeshBuffer {
     pub(crate) indices: Buffer<u32>,
     pub(crate) vertices: Buffer<Vertex>,
     pub(crate) offset: u64,
+    /// Set when any buffer grew since the last recorded bundle
+    pub(crate) grown: bool,
 }
 
 #[repr(C)]
@@ -24,6 +26,7 @@ impl MeshBuffer {
             indices: Buffer::new(device, 1024 * Indices::COUNT, wgpu::BufferUsages::INDEX),
             vertices: Buffer::new(device, 1024 * Vertices::COUNT, wgpu::BufferUsages::VERTEX),
             offset: 0,
+            grown: false,
         }
     }
 
@@ -105,7 +108,15 @@ impl Default for Vertices {
 impl Vertices {
     pub(crate) const COUNT: u64 = 4;
 
+    /// Axis aligned quad of `rect` in a `screen` sized viewport, sampling `uv` from the atlas when `textured`.
+    /// `element_index` points to the [`Element`](crate::Element) drawn by this quad
+    pub const fn quad(rect: &Rect, uv: Uv, screen: &Size, element_index: u32, textured: bool) -> Self {
+        Self::new(rect, uv, screen, element_index, textured as u8)
+    }
+
     pub(crate) const fn new(rect: &Rect, uv: Uv, screen: &Size, id: u32, atlas: u8) -> Self {
+        debug_assert_uv(&uv);
+
         let sx = screen.width;
         let sy = screen.height;
 
@@ -122,10 +133,130 @@ impl Vertices {
         ])
     }
 
+    /// Applies a general affine transform to the corners of the rect, around its center.
+    /// The translation part of the transform is added after the linear part.
+    pub(crate) fn new_transformed(
+        rect: &Rect,
+        transform: &Matrix3x2,
+        uv: Uv,
+        screen: &Size,
+        id: u32,
+        atlas: u8,
+    ) -> Self {
+        debug_assert_uv(&uv);
+
+        let center = rect_center(rect);
+        let half = Vec2f::new(rect.width / 2., rect.height / 2.);
+        let to_ndc = |corner: Vec2f| to_ndc(transform.transform_vec2f(corner) + center, screen);
+
+        let top_left = to_ndc(Vec2f::new(-half.x, -half.y));
+        let bot_left = to_ndc(Vec2f::new(-half.x, half.y));
+        let bot_right = to_ndc(Vec2f::new(half.x, half.y));
+        let top_right = to_ndc(Vec2f::new(half.x, -half.y));
+
+        Self([
+            Vertex { pos: top_left, uv: Vec2f::new(uv.min_x, uv.min_y), id, atlas },
+            Vertex { pos: bot_left, uv: Vec2f::new(uv.min_x, uv.max_y), id, atlas },
+            Vertex { pos: bot_right, uv: Vec2f::new(uv.max_x, uv.max_y), id, atlas },
+            Vertex { pos: top_right, uv: Vec2f::new(uv.max_x, uv.min_y), id, atlas },
+        ])
+    }
+
     #[inline(always)]
     pub(crate) const fn as_slice(&self) -> &[Vertex] {
         self.0.as_slice()
     }
+
+    /// Scale the uvs per axis, to follow the atlas they sample from when it grows
+    pub(crate) fn scale_uv(&mut self, scale: Size) {
+        self.0.iter_mut().for_each(|vertex| {
+            vertex.uv = Vec2f::new(vertex.uv.x * scale.width, vertex.uv.y * scale.height);
+        });
+    }
+}
+
+/// Fan triangulation of a convex polygon, the indices are relative to the first vertex
+pub(crate) struct PolygonMesh {
+    pub(crate) vertices: Vec<Vertex>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl PolygonMesh {
+    /// The points are relative to the top-left of the rect, and the transform is applied around its center
+    pub(crate) fn new(
+        points: &[Point],
+        rect: &Rect,
+        transform: &Matrix3x2,
+        screen: &Size,
+        id: u32,
+    ) -> Self {
+        let center = rect_center(rect);
+
+        let vertices = points
+            .iter()
+            .map(|point| {
+                let local = Vec2f::new(rect.x + point.x, rect.y + point.y) - center;
+                let pos = to_ndc(transform.transform_vec2f(local) + center, screen);
+                Vertex { pos, uv: Vec2f::default(), id, atlas: 0 }
+            })
+            .collect::<Vec<_>>();
+
+        let indices = (1..vertices.len().saturating_sub(1) as u32)
+            .flat_map(|i| [0, i, i + 1])
+            .collect();
+
+        Self {
+            vertices,
+            indices,
+        }
+    }
+
+    /// How many slots of the fixed [`Vertices`] & [`Indices`] layout this mesh occupies
+    pub(crate) fn slots(&self) -> u64 {
+        let vertices = (self.vertices.len() as u64).div_ceil(Vertices::COUNT);
+        let indices = (self.indices.len() as u64).div_ceil(Indices::COUNT);
+        vertices.max(indices)
+    }
+
+    /// Pad the vertices & indices with degenerate data to fill up the occupied slots,
+    /// offsetting the indices to the first vertex of `offset`
+    pub(crate) fn padded(mut self, offset: u64) -> (Vec<Vertex>, Vec<u32>) {
+        let slots = self.slots();
+        let base = (offset * Vertices::COUNT) as u32;
+
+        if let Some(last) = self.vertices.last().copied() {
+            self.vertices.resize((slots * Vertices::COUNT) as usize, last);
+        }
+
+        let mut indices = self.indices
+            .into_iter()
+            .map(|i| i + base)
+            .collect::<Vec<_>>();
+
+        if let Some(last) = indices.last().copied() {
+            indices.resize((slots * Indices::COUNT) as usize, last);
+        }
+
+        (self.vertices, indices)
+    }
+}
+
+#[inline(always)]
+const fn debug_assert_uv(uv: &Uv) {
+    debug_assert!(
+        uv.min_x >= 0. && uv.min_y >= 0. && uv.max_x <= 1. && uv.max_y <= 1.,
+        "uv must be normalized within 0..1"
+    );
+}
+
+#[inline(always)]
+fn rect_center(rect: &Rect) -> Vec2f {
+    Vec2f::new(rect.x + rect.width / 2., rect.y + rect.height / 2.)
+}
+
+#[inline(always)]
+fn to_ndc(p: Vec2f, screen: &Size) -> Vec2f {
+    Vec2f::new((p.x / screen.width) * 2.0 - 1.0, 1.0 - (p.y / screen.height) * 2.0)
 }
 
 impl std::fmt::Debug for Vertices {
@@ -144,3 +275,112 @@ impl std::fmt::Debug for Vertices {
         write!(f, "{s}")
     }
 }
+
+#[cfg(test)]
+mod mesh_test {
+    use super::*;
+
+    fn approx_eq(a: Vec2f, b: Vec2f) -> bool {
+        (a.x - b.x).abs() < 1e-5 && (a.y - b.y).abs() < 1e-5
+    }
+
+    #[test]
+    fn identity_matches_axis_aligned() {
+        let screen = Size::new(800., 600.);
+        let rect = Rect::new(100., 50., 200., 120.);
+
+        let aligned = Vertices::new(&rect, Uv::full(), &screen, 0, 0);
+        let transformed = Vertices::new_transformed(&rect, &Matrix3x2::identity(), Uv::full(), &screen, 0, 0);
+
+        for (a, b) in aligned.0.iter().zip(transformed.0.iter()) {
+            assert!(approx_eq(a.pos, b.pos), "{:?} != {:?}", a.pos, b.pos);
+        }
+    }
+
+    #[test]
+    fn quad_corners() {
+        let screen = Size::new(400., 200.);
+        let rect = Rect::new(100., 50., 200., 100.);
+        let uv = Uv { min_x: 0.25, min_y: 0.5, max_x: 0.75, max_y: 1. };
+
+        let vertices = Vertices::quad(&rect, uv, &screen, 7, true);
+
+        let expected = [
+            (Vec2f::new(-0.5,  0.5), Vec2f::new(0.25, 0.5)),
+            (Vec2f::new(-0.5, -0.5), Vec2f::new(0.25, 1.)),
+            (Vec2f::new( 0.5, -0.5), Vec2f::new(0.75, 1.)),
+            (Vec2f::new( 0.5,  0.5), Vec2f::new(0.75, 0.5)),
+        ];
+
+        for (vertex, (pos, uv)) in vertices.0.iter().zip(expected) {
+            assert!(approx_eq(vertex.pos, pos), "{:?} != {pos:?}", vertex.pos);
+            assert!(approx_eq(vertex.uv, uv), "{:?} != {uv:?}", vertex.uv);
+            assert_eq!((vertex.id, vertex.atlas), (7, 1));
+        }
+
+        let untextured = Vertices::quad(&rect, Uv::full(), &screen, 0, false);
+        assert!(untextured.0.iter().all(|vertex| vertex.atlas == 0));
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn quad_uv_out_of_range() {
+        let uv = Uv { min_x: 0., min_y: 0., max_x: 1.5, max_y: 1. };
+        Vertices::quad(&Rect::new(0., 0., 1., 1.), uv, &Size::new(1., 1.), 0, false);
+    }
+
+    #[test]
+    fn rotate_unit_rect() {
+        let screen = Size::new(2., 2.);
+        let rect = Rect::new(0.5, 0.5, 1., 1.);
+        let rotate = Matrix3x2::from_rotate_rad(std::f32::consts::FRAC_PI_2);
+
+        let vertices = Vertices::new_transformed(&rect, &rotate, Uv::full(), &screen, 0, 0);
+
+        // a quarter turn moves every corner onto its neighbour
+        let expected = [
+            Vec2f::new(-0.5, -0.5),
+            Vec2f::new( 0.5, -0.5),
+            Vec2f::new( 0.5,  0.5),
+            Vec2f::new(-0.5,  0.5),
+        ];
+
+        for (vertex, expected) in vertices.0.iter().zip(expected) {
+            assert!(approx_eq(vertex.pos, expected), "{:?} != {expected:?}", vertex.pos);
+        }
+    }
+
+    #[test]
+    fn triangle_polygon() {
+        let screen = Size::new(800., 600.);
+        let rect = Rect::new(0., 0., 100., 100.);
+        let points = [Point::new(50., 0.), Point::new(0., 100.), Point::new(100., 100.)];
+
+        let mesh = PolygonMesh::new(&points, &rect, &Matrix3x2::identity(), &screen, 0);
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        assert_eq!(mesh.slots(), 1);
+
+        let (vertices, indices) = mesh.padded(2);
+        assert_eq!(vertices.len(), Vertices::COUNT as usize);
+        assert_eq!(indices, vec![8, 9, 10, 10, 10, 10]);
+    }
+
+    #[test]
+    fn hexagon_polygon() {
+        let screen = Size::new(800., 600.);
+        let rect = Rect::new(0., 0., 100., 100.);
+        let points = (0..6)
+            .map(|i| {
+                let (sin, cos) = (i as f32 * std::f32::consts::FRAC_PI_3).sin_cos();
+                Point::new(50. + cos * 50., 50. + sin * 50.)
+            })
+            .collect::<Vec<_>>();
+
+        let mesh = PolygonMesh::new(&points, &rect, &Matrix3x2::identity(), &screen, 0);
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.indices.len(), 4 * 3);
+        assert_eq!(mesh.slots(), 2);
+    }
+}
@@ -1,18 +1,19 @@
 use std::sync::Arc;
 
+use wgpu::util::RenderEncoder;
 use winit::window::Window;
 use winit::dpi::PhysicalSize;
-use aplite_types::{Rect, Matrix3x2, Size, PaintRef, CornerRadius};
+use aplite_types::{Color, Rect, Matrix3x2, Point, Size, PaintRef, CornerRadius};
+use aplite_types::theme::basic;
 
-// use super::RenderError;
-use super::InitiationError;
+use super::{InitiationError, RenderError};
 
 use crate::TextureRef;
 use crate::atlas::{Atlas, Uv};
-use crate::element::{Element, Shape};
+use crate::element::{BorderStyle, Element, Shape};
 use crate::screen::Screen;
 use crate::storage::StorageBuffers;
-use crate::mesh::{Indices, MeshBuffer, Vertices};
+use crate::mesh::{Indices, MeshBuffer, PolygonMesh, Vertex, Vertices};
 use crate::util::Sampler;
 use crate::glyph::FontHandler;
 
@@ -21,9 +22,14 @@ pub struct Renderer {
     pub queue: wgpu::Queue,
 
     // FIXME: maybe separating these was good?
-    surface: wgpu::Surface<'static>,
+    target: RenderTarget,
     config: wgpu::SurfaceConfiguration,
     bundle: Option<wgpu::RenderBundle>,
+    clear_pipeline: wgpu::RenderPipeline,
+    clear_color: Color,
+    damage: Vec<Rect>,
+    /// The rects drawn this frame, [`Some`] while the debug overlay is on
+    debug_overlay: Option<Vec<(Rect, Matrix3x2)>>,
 
     // FIXME: not needed?
     screen: Screen,
@@ -35,20 +41,132 @@ pub struct Renderer {
     texture_atlas: Atlas,
     font_handler: FontHandler,
     texture_bind_group: wgpu::BindGroup,
+    atlas_uses: AtlasUses,
 
     sampler: Sampler,
     offset: u64,
+    element_count: usize,
+    caps: RendererCaps,
 }
 
-const CLEAR_COLOR: wgpu::Color = wgpu::Color {
-    r: 0x6 as f64 / u8::MAX as f64,
-    g: 0x6 as f64 / u8::MAX as f64,
-    b: 0x6 as f64 / u8::MAX as f64,
-    a: 1.0
-};
+enum RenderTarget {
+    Surface(wgpu::Surface<'static>),
+    Offscreen(wgpu::Texture),
+}
+
+impl RenderTarget {
+    fn offscreen_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen target"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    fn configure(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
+        match self {
+            Self::Surface(surface) => surface.configure(device, config),
+            Self::Offscreen(texture) => *texture = Self::offscreen_texture(device, config),
+        }
+    }
+
+    /// The surface hands out rotating textures whose previous content isn't preserved,
+    /// so only the offscreen target can be partially repainted
+    fn preserves_contents(&self) -> bool {
+        matches!(self, Self::Offscreen(_))
+    }
+
+    /// The offscreen target has nothing to present, hence no [`wgpu::SurfaceTexture`]
+    fn acquire(&self) -> Result<(wgpu::TextureView, Option<wgpu::SurfaceTexture>), RenderError> {
+        match self {
+            Self::Surface(surface) => {
+                let frame = surface.get_current_texture()?;
+                let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+                Ok((view, Some(frame)))
+            },
+            Self::Offscreen(texture) => {
+                Ok((texture.create_view(&wgpu::TextureViewDescriptor::default()), None))
+            },
+        }
+    }
+}
+
+/// Initial sizes of the texture atlases. Both grow on demand up to the device limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererConfig {
+    /// Atlas holding the images
+    pub atlas_size: Size,
+    /// Atlas holding the rasterized glyphs
+    pub glyph_atlas_size: Size,
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        Self {
+            atlas_size: Size::square(4096.),
+            glyph_atlas_size: Size::square(2048.),
+        }
+    }
+}
+
+/// What the adapter the renderer runs on supports, see [`Renderer::capabilities`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RendererCaps {
+    /// The largest width or height of a 2d texture, bounding the atlases
+    pub max_texture_dimension: u32,
+    pub max_buffer_size: u64,
+    /// The MSAA sample counts usable with the target format, always including 1
+    pub sample_counts: Vec<u32>,
+    /// Empty for the offscreen renderer, which has nothing to present
+    pub present_modes: Vec<wgpu::PresentMode>,
+}
+
+impl RendererCaps {
+    fn new(
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        present_modes: Vec<wgpu::PresentMode>,
+    ) -> Self {
+        let limits = device.limits();
+
+        Self {
+            max_texture_dimension: limits.max_texture_dimension_2d,
+            max_buffer_size: limits.max_buffer_size,
+            sample_counts: adapter
+                .get_texture_format_features(format)
+                .flags
+                .supported_sample_counts(),
+            present_modes,
+        }
+    }
+}
+
+const CLEAR_COLOR: Color = Color::new(0x6, 0x6, 0x6, 0xff);
+const DEBUG_OUTLINE_COLOR: Color = Color::new(0xff, 0x0, 0xff, 0xff);
+
+fn wgpu_color(color: &Color) -> wgpu::Color {
+    let channel = |val: u8| val as f64 / u8::MAX as f64;
+
+    wgpu::Color {
+        r: channel(color.r),
+        g: channel(color.g),
+        b: channel(color.b),
+        a: channel(color.a),
+    }
+}
 
 impl Renderer {
-    pub async fn new(window: Arc<Window>) -> Result<Self, InitiationError> {
+    pub async fn new(window: Arc<Window>, renderer_config: RendererConfig) -> Result<Self, InitiationError> {
         let size = window.inner_size();
         let scale_factor = window.scale_factor();
 
@@ -92,36 +210,96 @@ impl Renderer {
 
         surface.configure(&device, &config);
 
+        let caps = RendererCaps::new(&adapter, &device, format, surface_capabilites.present_modes);
         let logical: winit::dpi::LogicalSize<f32> = size.to_logical(scale_factor);
         let screen_size = Size::new(logical.width, logical.height);
-        let screen = Screen::new(&device, screen_size, scale_factor);
 
-        let s = 1024;
-        let texture_atlas = Atlas::new(&device, Size::square((s * 4) as f32), "atlas");
-        let font_handler = FontHandler::new(&device, Size::square((s * 2) as f32));
+        Ok(Self::from_parts(
+            device,
+            queue,
+            RenderTarget::Surface(surface),
+            config,
+            screen_size,
+            scale_factor,
+            renderer_config,
+            caps,
+        ))
+    }
+
+    /// Headless renderer without a window, drawing into an offscreen RGBA8 texture of the given size.
+    /// Useful for automated rendering tests together with [`read_pixels`](Self::read_pixels)
+    pub async fn new_offscreen(size: Size) -> Result<Self, InitiationError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: backend(),
+            ..Default::default()
+        });
+
+        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+
+        let (device, queue) = adapter.request_device(&wgpu::DeviceDescriptor {
+                required_features: wgpu::Features::empty(),
+                ..Default::default()
+            },
+        ).await?;
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            width: size.width as u32,
+            height: size.height as u32,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            desired_maximum_frame_latency: 2,
+            view_formats: vec![],
+        };
+
+        let texture = RenderTarget::offscreen_texture(&device, &config);
+        let caps = RendererCaps::new(&adapter, &device, config.format, Vec::new());
+
+        Ok(Self::from_parts(
+            device,
+            queue,
+            RenderTarget::Offscreen(texture),
+            config,
+            size,
+            1.0,
+            RendererConfig::default(),
+            caps,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_parts(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        target: RenderTarget,
+        config: wgpu::SurfaceConfiguration,
+        screen_size: Size,
+        scale_factor: f64,
+        renderer_config: RendererConfig,
+        caps: RendererCaps,
+    ) -> Self {
+        let mut screen = Screen::new(&device, screen_size, scale_factor);
+        screen.write(&device, &queue, Matrix3x2::identity());
+
+        let texture_atlas = Atlas::new(&device, renderer_config.atlas_size, "atlas");
+        let font_handler = FontHandler::new(&device, renderer_config.glyph_atlas_size);
         let sampler = Sampler::new(&device);
 
-        let texture_bind_group = Self::bind_group(
-            &device,
-            &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&texture_atlas.view()),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::TextureView(&font_handler.view()),
-                }
-            ]
-        );
+        let texture_bind_group = Self::texture_bind_group(&device, &texture_atlas, &font_handler);
 
         let storage = StorageBuffers::new(&device);
         let mesh = MeshBuffer::new(&device);
+        let clear_pipeline = Pipeline::new_clear_pipeline(&device, config.format);
 
-        Ok(Self {
+        Self {
+            clear_pipeline,
+            clear_color: CLEAR_COLOR,
+            damage: Vec::new(),
+            debug_overlay: None,
             device,
             queue,
-            surface,
+            target,
             config,
             bundle: None,
             storage,
@@ -129,10 +307,33 @@ impl Renderer {
             font_handler,
             texture_atlas,
             texture_bind_group,
+            atlas_uses: AtlasUses::default(),
             mesh,
             screen,
             offset: 0,
-        })
+            element_count: 0,
+            caps,
+        }
+    }
+
+    fn texture_bind_group(
+        device: &wgpu::Device,
+        texture_atlas: &Atlas,
+        font_handler: &FontHandler,
+    ) -> wgpu::BindGroup {
+        Self::bind_group(
+            device,
+            &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&texture_atlas.view()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&font_handler.view()),
+                }
+            ]
+        )
     }
 
     fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
@@ -174,13 +375,88 @@ impl Renderer {
         })
     }
 
+    fn render_pipeline(device: &wgpu::Device, format: wgpu::TextureFormat) -> wgpu::RenderPipeline {
+        let bind_group_layouts = &[
+            &Screen::bind_group_layout(device),
+            &StorageBuffers::bind_group_layout(device),
+            &Self::bind_group_layout(device),
+            &Sampler::bind_group_layout(device),
+        ];
+
+        Pipeline::new_render_pipeline(
+            device,
+            format,
+            &[MeshBuffer::vertice_layout()],
+            bind_group_layouts
+        )
+    }
+
+    /// The color filling the target before anything is drawn, also used to clear the damaged regions
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+    }
+
+    pub fn clear_color(&self) -> Color {
+        self.clear_color
+    }
+
+    /// Outline the rect of every drawn element with a 1px line on top of the content, to debug the layout
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled.then(Vec::new);
+    }
+
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay.is_some()
+    }
+
+    /// The number of elements in the frame passed to the last [`finish`](Self::finish), including the debug overlay
+    pub fn element_count(&self) -> usize {
+        self.element_count
+    }
+
+    fn draw_debug_overlay(&mut self) {
+        let Some(mut outlines) = self.debug_overlay.as_mut().map(std::mem::take) else { return };
+
+        let mut scene = self.scene();
+        scene.overlay = None;
+
+        let background = basic::TRANSPARENT;
+        for (rect, transform) in &outlines {
+            scene.draw_rect(
+                rect,
+                transform,
+                &PaintRef::Color(&background),
+                &PaintRef::Color(&DEBUG_OUTLINE_COLOR),
+                &1.0,
+            );
+        }
+
+        outlines.clear();
+        self.debug_overlay = Some(outlines);
+    }
+
+    /// Query before picking an atlas size or enabling MSAA
+    pub fn capabilities(&self) -> RendererCaps {
+        self.caps.clone()
+    }
+
     #[inline(always)]
     pub const fn scale_factor(&self) -> f64 {
         self.screen.scale_factor
     }
 
-    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+    /// The logical size of the surface changes with the scale factor, `f` receives the new one
+    pub fn set_scale_factor(&mut self, scale_factor: f64, f: impl FnOnce(Size)) {
         self.screen.scale_factor = scale_factor;
+        self.update_screen_transform(f);
+    }
+
+    pub fn logical_to_physical(&self, point: Point) -> Point {
+        self.screen.logical_to_physical(point)
+    }
+
+    pub fn physical_to_logical(&self, point: Point) -> Point {
+        self.screen.physical_to_logical(point)
     }
 
     pub fn surface_size(&self) -> Size {
@@ -190,9 +466,15 @@ impl Renderer {
     pub fn resize(&mut self, new_size: PhysicalSize<u32>, f: impl FnOnce(Size)) {
         self.config.width = new_size.width;
         self.config.height = new_size.height;
-        self.surface.configure(&self.device, &self.config);
+        self.target.configure(&self.device, &self.config);
+        self.update_screen_transform(f);
+    }
 
-        let logical: winit::dpi::LogicalSize<f32> = new_size.to_logical(self.scale_factor());
+    /// Map the initial screen resolution, which the vertices are computed against,
+    /// onto the current logical size of the surface
+    fn update_screen_transform(&mut self, f: impl FnOnce(Size)) {
+        let physical = PhysicalSize::new(self.config.width, self.config.height);
+        let logical: winit::dpi::LogicalSize<f32> = physical.to_logical(self.scale_factor());
         let res = self.screen.screen_resolution;
         let ns = Size::new(logical.width, logical.height);
 
@@ -209,6 +491,11 @@ impl Renderer {
 
     pub fn begin(&mut self) {
         self.mesh.offset = 0;
+        self.atlas_uses.clear();
+
+        if let Some(outlines) = self.debug_overlay.as_mut() {
+            outlines.clear();
+        }
     }
 
     #[inline(always)]
@@ -221,27 +508,46 @@ impl Renderer {
             mesh: &mut self.mesh,
             texture_atlas: &mut self.texture_atlas,
             font_handler: &mut self.font_handler,
+            atlas_uses: &mut self.atlas_uses,
+            damage: &mut self.damage,
+            overlay: self.debug_overlay.as_mut(),
             scale: self.screen.scale_factor as f32,
         }
     }
 
-    pub fn finish(&mut self, window: &Window) {
-        if self.mesh.offset == 0 { return }
+    /// Submit the frame. The window is notified before presenting the surface,
+    /// and is not needed for a renderer created with [`new_offscreen`](Self::new_offscreen).
+    /// Nothing is submitted when the surface texture can't be acquired
+    pub fn finish(&mut self, window: Option<&Window>) -> Result<(), RenderError> {
+        self.draw_debug_overlay();
+        self.element_count = self.mesh.offset as usize;
+
+        if self.mesh.offset == 0 { return Ok(()) }
+
+        let (view, frame) = self.target.acquire()?;
 
-        if self.bundle.is_none() || self.mesh.offset != self.offset {
-            let bind_group_layouts = &[
-                &Screen::bind_group_layout(&self.device),
-                &StorageBuffers::bind_group_layout(&self.device),
-                &Self::bind_group_layout(&self.device),
-                &Sampler::bind_group_layout(&self.device),
-            ];
+        let mut encoder = self.device
+            .create_command_encoder(
+                &wgpu::CommandEncoderDescriptor { label: Some("render encoder") }
+            );
+
+        let atlas_grown = self.texture_atlas.update(&self.device, &mut encoder);
+        let glyph_grown = self.font_handler.update(&self.device, &mut encoder);
 
-            let pipeline = Pipeline::new_render_pipeline(
+        if atlas_grown || glyph_grown {
+            self.texture_bind_group = Self::texture_bind_group(
                 &self.device,
-                self.config.format,
-                &[MeshBuffer::vertice_layout()],
-                bind_group_layouts
+                &self.texture_atlas,
+                &self.font_handler,
             );
+            self.bundle = None;
+            self.rewrite_stale_uvs();
+        }
+
+        let grown = std::mem::take(&mut self.mesh.grown);
+
+        if self.bundle.is_none() || self.mesh.offset != self.offset || grown {
+            let pipeline = Self::render_pipeline(&self.device, self.config.format);
 
             let bundle_encoder = self.encode(&pipeline);
             let render_bundle = bundle_encoder.finish(&Default::default());
@@ -249,33 +555,30 @@ impl Renderer {
             self.bundle = Some(render_bundle);
         }
 
-        let surface = self.surface.get_current_texture().unwrap();
-        let view = surface.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let damage = damage_union(&self.damage)
+            .filter(|_| self.target.preserves_contents())
+            .and_then(|rect| self.scissor_rect(&rect));
 
-        let desc = wgpu::RenderPassColorAttachment {
-            view: &view,
-            ops: wgpu::Operations {
-                load: wgpu::LoadOp::Clear(CLEAR_COLOR),
-                store: wgpu::StoreOp::Store,
-            },
-            resolve_target: None,
-            depth_slice: None,
-        };
-
-        let mut encoder = self.device
-            .create_command_encoder(
-                &wgpu::CommandEncoderDescriptor { label: Some("render encoder") }
-            );
+        self.damage.clear();
 
-        self.texture_atlas.update(&self.device, &mut encoder);
-        self.font_handler.update(&self.device, &mut encoder);
+        let load = match damage {
+            Some(_) => wgpu::LoadOp::Load,
+            None => wgpu::LoadOp::Clear(wgpu_color(&self.clear_color)),
+        };
 
         let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("render pass"),
-            color_attachments: &[Some(desc)],
+            color_attachments: &[Some(color_attachment(&view, load))],
             ..Default::default()
         });
 
+        if let Some([x, y, width, height]) = damage {
+            pass.set_scissor_rect(x, y, width, height);
+            pass.set_blend_constant(wgpu_color(&self.clear_color));
+            pass.set_pipeline(&self.clear_pipeline);
+            pass.draw(0..3, 0..1);
+        }
+
         if let Some(render_bundle) = self.bundle.as_ref() {
             pass.execute_bundles([render_bundle]);
         }
@@ -283,10 +586,66 @@ impl Renderer {
         self.offset = self.mesh.offset;
         drop(pass);
 
-        window.pre_present_notify();
+        if let Some(window) = window {
+            window.pre_present_notify();
+        }
 
         self.queue.submit([encoder.finish()]);
-        surface.present();
+
+        if let Some(frame) = frame {
+            frame.present();
+        }
+
+        Ok(())
+    }
+
+    /// Quads and border images drawn before an atlas grew this frame hold uvs relative to its previous size
+    fn rewrite_stale_uvs(&mut self) {
+        let atlas_size = self.texture_atlas.allocator.bound.size();
+        let glyph_atlas_size = self.font_handler.atlas.allocator.bound.size();
+
+        let images = self.atlas_uses.images.iter_mut().map(|quad| (quad, atlas_size));
+        let glyphs = self.atlas_uses.glyphs.iter_mut().map(|quad| (quad, glyph_atlas_size));
+
+        for ((offset, vertices, size), current) in images.chain(glyphs) {
+            if *size == current { continue }
+
+            vertices.scale_uv(*size / current);
+            *size = current;
+            self.mesh.vertices.write(&self.device, &self.queue, *offset * Vertices::COUNT, vertices.as_slice());
+        }
+
+        for (offset, element, size) in self.atlas_uses.borders.iter_mut() {
+            if *size == atlas_size { continue }
+
+            element.scale_border_uv(*size / atlas_size);
+            *size = atlas_size;
+            self.storage.elements.write(&self.device, &self.queue, *offset, &[*element]);
+        }
+    }
+
+    /// [`finish`](Self::finish) the frame, reconfiguring the surface and trying once more
+    /// if the error is [recoverable](RenderError::is_recoverable)
+    pub fn render_with_retry(&mut self, window: Option<&Window>) -> Result<(), RenderError> {
+        match self.finish(window) {
+            Err(err) if err.is_recoverable() => {
+                self.target.configure(&self.device, &self.config);
+                self.finish(window)
+            }
+            result => result,
+        }
+    }
+
+    /// Convert a logical damage rect into a physical scissor rect clamped to the target,
+    /// [`None`] when nothing is left to repaint
+    fn scissor_rect(&self, rect: &Rect) -> Option<[u32; 4]> {
+        let scale = self.scale_factor() as f32;
+        let x = (rect.x * scale).floor().max(0.) as u32;
+        let y = (rect.y * scale).floor().max(0.) as u32;
+        let max_x = ((rect.max_x() * scale).ceil().max(0.) as u32).min(self.config.width);
+        let max_y = ((rect.max_y() * scale).ceil().max(0.) as u32).min(self.config.height);
+
+        (max_x > x && max_y > y).then(|| [x, y, max_x - x, max_y - y])
     }
 
     fn encode<'a>(&'a self, pipeline: &'a wgpu::RenderPipeline) -> wgpu::RenderBundleEncoder<'a> {
@@ -299,23 +658,148 @@ impl Renderer {
         };
 
         let mut encoder = self.device.create_render_bundle_encoder(&desc);
+        self.record(&mut encoder, pipeline);
+
+        encoder
+    }
 
+    fn record<'a>(&'a self, encoder: &mut impl RenderEncoder<'a>, pipeline: &'a wgpu::RenderPipeline) {
         encoder.set_pipeline(pipeline);
 
         encoder.set_index_buffer(self.mesh.indices_slice(), wgpu::IndexFormat::Uint32);
         encoder.set_vertex_buffer(0, self.mesh.vertices_slice());
 
-        encoder.set_bind_group(0, &self.screen.bind_group, &[]);
-        encoder.set_bind_group(1, &self.storage.bind_group, &[]);
-        encoder.set_bind_group(2, &self.texture_bind_group, &[]);
-        encoder.set_bind_group(3, &self.sampler.bind_group, &[]);
+        encoder.set_bind_group(0, Some(&self.screen.bind_group), &[]);
+        encoder.set_bind_group(1, Some(&self.storage.bind_group), &[]);
+        encoder.set_bind_group(2, Some(&self.texture_bind_group), &[]);
+        encoder.set_bind_group(3, Some(&self.sampler.bind_group), &[]);
 
         encoder.draw_indexed(0..self.mesh.offset as u32 * Indices::COUNT as u32, 0, 0..1);
+    }
 
-        encoder
+    /// Render the current scene into an offscreen texture, and read it back as tightly packed RGBA8 bytes.
+    /// Images drawn since the last [`finish`](Self::finish) are not uploaded to the atlas yet.
+    pub fn read_pixels(&self) -> Result<(Size, Vec<u8>), RenderError> {
+        let width = self.config.width;
+        let height = self.config.height;
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("read pixels texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let pipeline = Self::render_pipeline(&self.device, format);
+
+        let mut encoder = self.device
+            .create_command_encoder(
+                &wgpu::CommandEncoderDescriptor { label: Some("read pixels encoder") }
+            );
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("read pixels pass"),
+            color_attachments: &[Some(color_attachment(&view, wgpu::LoadOp::Clear(wgpu_color(&self.clear_color))))],
+            ..Default::default()
+        });
+
+        if self.mesh.offset > 0 {
+            self.record(&mut pass, &pipeline);
+        }
+
+        drop(pass);
+
+        let bytes = read_texture(&self.device, &self.queue, encoder, &texture)?;
+
+        Ok((Size::new(width as f32, height as f32), bytes))
     }
 }
 
+/// The region to repaint for a frame, [`None`] when nothing has been marked dirty
+fn damage_union(rects: &[Rect]) -> Option<Rect> {
+    rects
+        .iter()
+        .copied()
+        .reduce(|acc, rect| acc.union(&rect))
+}
+
+fn color_attachment(
+    view: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+) -> wgpu::RenderPassColorAttachment<'_> {
+    wgpu::RenderPassColorAttachment {
+        view,
+        ops: wgpu::Operations {
+            load,
+            store: wgpu::StoreOp::Store,
+        },
+        resolve_target: None,
+        depth_slice: None,
+    }
+}
+
+/// Copy an RGBA8 texture into a mappable buffer, and strip the row padding required by the copy alignment
+fn read_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    mut encoder: wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+) -> Result<Vec<u8>, RenderError> {
+    let width = texture.width();
+    let height = texture.height();
+    let unpadded = width * 4;
+    let padded = padded_bytes_per_row(width);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("read pixels buffer"),
+        size: (padded * height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+
+    queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely())?;
+
+    let bytes = slice
+        .get_mapped_range()
+        .chunks_exact(padded as usize)
+        .flat_map(|row| &row[..unpadded as usize])
+        .copied()
+        .collect();
+
+    buffer.unmap();
+
+    Ok(bytes)
+}
+
+#[inline(always)]
+const fn padded_bytes_per_row(width: u32) -> u32 {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    (width * 4).div_ceil(align) * align
+}
+
 pub struct Scene<'a> {
     device: &'a wgpu::Device,
     queue: &'a wgpu::Queue,
@@ -323,53 +807,190 @@ pub struct Scene<'a> {
     mesh: &'a mut MeshBuffer,
     texture_atlas: &'a mut Atlas,
     font_handler: &'a mut FontHandler,
+    atlas_uses: &'a mut AtlasUses,
+    damage: &'a mut Vec<Rect>,
+    overlay: Option<&'a mut Vec<(Rect, Matrix3x2)>>,
     size: &'a Size,
     scale: f32,
 }
 
+/// What has been written this frame with uvs of an atlas, along with the atlas size they were computed against.
+/// The atlas may still grow before the frame is submitted, see [`Renderer::rewrite_stale_uvs`]
+#[derive(Default)]
+struct AtlasUses {
+    images: Vec<(u64, Vertices, Size)>,
+    glyphs: Vec<(u64, Vertices, Size)>,
+    borders: Vec<(u64, Element, Size)>,
+}
+
+impl AtlasUses {
+    fn clear(&mut self) {
+        self.images.clear();
+        self.glyphs.clear();
+        self.borders.clear();
+    }
+}
+
+/// CPU side data of consecutive quads, see [`Scene::draw_batch`]
+struct Batch {
+    indices: Vec<u32>,
+    vertices: Vec<Vertex>,
+    elements: Vec<Element>,
+}
+
+impl Batch {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            indices: Vec::with_capacity(capacity * Indices::COUNT as usize),
+            vertices: Vec::with_capacity(capacity * Vertices::COUNT as usize),
+            elements: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.indices.clear();
+        self.vertices.clear();
+        self.elements.clear();
+    }
+}
+
 pub struct DrawArgs<'a> {
     pub rect: &'a Rect,
     pub transform: &'a Matrix3x2,
     pub background_paint: &'a PaintRef<'a>,
     pub border_paint: &'a PaintRef<'a>,
     pub border_width: &'a f32,
+    pub border_style: &'a BorderStyle,
     pub shape: &'a Shape,
     pub corner_radius: &'a CornerRadius,
+    pub opacity: &'a f32,
+}
+
+impl DrawArgs<'_> {
+    pub(crate) fn element(&self, screen: &Size) -> Element {
+        let mut element = Element::new(self.rect.size() / screen)
+            .with_shape(self.shape)
+            .with_corner_radius(&self.corner_radius.clamped(self.rect.size()))
+            .with_border_width(*self.border_width, screen)
+            .with_border_style(self.border_style, screen)
+            .with_opacity(*self.opacity);
+
+        // image borders are allocated in the atlas by the scene
+        if let PaintRef::Color(color) = self.border_paint {
+            element.border = color.pack_u32();
+        }
+
+        if let PaintRef::Color(rgba) = self.background_paint {
+            element.background = rgba.pack_u32();
+        }
+
+        element
+    }
 }
 
 // FIXME: this feels immediate mode to me, idk
 impl Scene<'_> {
-    pub fn draw(
-        &mut self,
-        DrawArgs {
-            rect,
-            transform,
-            background_paint,
-            border_paint,
-            border_width,
-            shape,
-            corner_radius,
-        }: DrawArgs<'_>,
-    ) {
+    pub fn draw(&mut self, args: DrawArgs<'_>) {
+        self.outline(&args);
+
+        if let Shape::Polygon(points) = args.shape {
+            return self.draw_polygon(points, &args);
+        }
+
         let offset = self.mesh.offset;
+        let element = self.element(&args);
+        let vertices = self.quad(&args, offset);
+
+        self.add_indices();
+        self.add_vertices(vertices);
+        self.add_element(element);
+
+        self.mesh.offset += 1;
+    }
+
+    /// Same as calling [`draw`](Self::draw) for each of `args`, but the data is accumulated first
+    /// and written with a single call per buffer. Polygons are drawn in between, splitting the batch
+    pub fn draw_batch(&mut self, args: &[DrawArgs<'_>]) {
+        let mut batch = Batch::with_capacity(args.len());
+
+        for args in args {
+            self.outline(args);
+
+            if let Shape::Polygon(points) = args.shape {
+                self.flush(&mut batch);
+                self.draw_polygon(points, args);
+                continue;
+            }
+
+            let offset = self.mesh.offset;
+            let vertices = self.quad(args, offset);
+
+            batch.indices.extend_from_slice(Indices::new(offset as _).as_slice());
+            batch.vertices.extend_from_slice(vertices.as_slice());
+            let element = self.element(args);
+            batch.elements.push(element);
+
+            self.mesh.offset += 1;
+        }
+
+        self.flush(&mut batch);
+    }
+
+    fn outline(&mut self, args: &DrawArgs<'_>) {
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.push((*args.rect, *args.transform));
+        }
+    }
 
-        let mut element = Element::new(rect.size() / self.size)
-            .with_shape(*shape)
-            .with_corner_radius(corner_radius)
-            .with_border_width(*border_width / self.size.width);
+    /// Write the batched quads, which end at the current offset
+    fn flush(&mut self, batch: &mut Batch) {
+        if batch.elements.is_empty() { return }
 
-        match border_paint {
-            PaintRef::Color(color) => element.border = color.pack_u32(),
-            PaintRef::Image(_) => todo!("not implemented yet"),
+        let start = self.mesh.offset - batch.elements.len() as u64;
+
+        self.mesh.grown |= self.mesh.indices.write(self.device, self.queue, start * Indices::COUNT, &batch.indices);
+        self.mesh.grown |= self.mesh.vertices.write(self.device, self.queue, start * Vertices::COUNT, &batch.vertices);
+
+        if self.storage.elements.write(self.device, self.queue, start, &batch.elements) {
+            self.storage_grown()
         }
 
-        let vertices = match background_paint {
-            PaintRef::Color(rgba) => {
-                element.background = rgba.pack_u32();
+        batch.clear();
+    }
+
+    fn element(&mut self, args: &DrawArgs<'_>) -> Element {
+        let element = args.element(self.size);
+
+        match args.border_paint {
+            PaintRef::Color(_) => element,
+            PaintRef::Image(image_ref) => {
+                let uv = self.texture_atlas.append(&TextureRef::new(
+                    image_ref.width,
+                    image_ref.height,
+                    image_ref.bytes.clone()
+                ));
+
+                // the image doesn't fit even at the largest atlas size
+                let Some(uv) = uv else {
+                    return element.with_border_width(0., self.size)
+                };
+
+                let element = element.with_border_image(uv);
+                let size = self.texture_atlas.allocator.bound.size();
+                self.atlas_uses.borders.push((self.mesh.offset, element, size));
+
+                element
+            }
+        }
+    }
 
-                Vertices::new(
-                    rect,
-                    Uv::DEFAULT,
+    fn quad(&mut self, args: &DrawArgs<'_>, offset: u64) -> Vertices {
+        match args.background_paint {
+            PaintRef::Color(_) => {
+                Vertices::new_transformed(
+                    args.rect,
+                    args.transform,
+                    Uv::full(),
                     self.size,
                     offset as _,
                     0,
@@ -384,22 +1005,48 @@ impl Scene<'_> {
                     ))
                     .unwrap();
 
-                Vertices::new(
-                    rect,
+                let vertices = Vertices::new_transformed(
+                    args.rect,
+                    args.transform,
                     uv,
                     self.size,
                     offset as _,
                     1,
-                )
+                );
+
+                let size = self.texture_atlas.allocator.bound.size();
+                self.atlas_uses.images.push((offset, vertices, size));
+
+                vertices
             }
-        };
+        }
+    }
 
-        self.add_indices();
-        self.add_vertices(vertices);
+    fn draw_polygon(&mut self, points: &[Point], args: &DrawArgs<'_>) {
+        let offset = self.mesh.offset;
+        let mesh = PolygonMesh::new(
+            points,
+            args.rect,
+            args.transform,
+            self.size,
+            offset as _,
+        );
+
+        if mesh.indices.is_empty() { return }
+
+        let slots = mesh.slots();
+        let (vertices, indices) = mesh.padded(offset);
+
+        self.mesh.grown |= self.mesh.indices.write(self.device, self.queue, offset * Indices::COUNT, &indices);
+        self.mesh.grown |= self.mesh.vertices.write(self.device, self.queue, offset * Vertices::COUNT, &vertices);
+        let element = self.element(args);
         self.add_element(element);
-        self.add_transform(transform);
 
-        self.mesh.offset += 1;
+        self.mesh.offset += slots;
+    }
+
+    pub fn measure_text(&mut self, text: &str, font_size: f32) -> Size {
+        self.font_handler.measure(text, font_size)
     }
 
     pub fn draw_text(
@@ -407,7 +1054,6 @@ impl Scene<'_> {
         text: &str,
         font_size: f32,
         rect: &Rect,
-        transform: &Matrix3x2,
         color: &aplite_types::Color,
     ) {
         let text_data = self.font_handler.rasterize_text(
@@ -420,14 +1066,17 @@ impl Scene<'_> {
         text_data.iter().for_each(|(uv, glyph)| {
             let offset = self.mesh.offset;
 
-            let vertices = Vertices::new(
+            let vertices = Vertices::quad(
                 &Rect::from_array(*glyph),
                 *uv,
                 self.size,
                 offset as _,
-                1,
+                true,
             );
 
+            let size = self.font_handler.atlas.allocator.bound.size();
+            self.atlas_uses.glyphs.push((offset, vertices, size));
+
             let packed_color = color.pack_u32();
 
             let element = Element {
@@ -435,14 +1084,19 @@ impl Scene<'_> {
                 background: packed_color,
                 border: packed_color,
                 corners: 0,
-                shape: Shape::Text as u32,
+                shape: Shape::Text.id(),
                 border_width: 0.,
+                opacity: 1.,
+                border_texture: 0,
+                border_uv: [0.; 4],
+                border_style: 0,
+                border_dash: [0.; 2],
+                border_gap: [0.; 2],
             };
 
             self.add_indices();
             self.add_vertices(vertices);
             self.add_element(element);
-            self.add_transform(transform);
 
             self.mesh.offset += 1;
         });
@@ -463,8 +1117,10 @@ impl Scene<'_> {
             background_paint,
             border_paint,
             border_width,
+            border_style: &BorderStyle::Solid,
             shape: &Shape::Rect,
             corner_radius: &CornerRadius::splat(0),
+            opacity: &1.0,
         });
     }
 
@@ -483,8 +1139,10 @@ impl Scene<'_> {
             background_paint,
             border_paint,
             border_width,
+            border_style: &BorderStyle::Solid,
             shape: &Shape::RoundedRect,
             corner_radius,
+            opacity: &1.0,
         });
     }
 
@@ -502,13 +1160,15 @@ impl Scene<'_> {
             background_paint,
             border_paint,
             border_width,
+            border_style: &BorderStyle::Solid,
             shape: &Shape::Circle,
             corner_radius: &CornerRadius::splat(0),
+            opacity: &1.0,
         });
     }
 
     fn add_indices(&mut self) {
-        self.mesh.indices.write(
+        self.mesh.grown |= self.mesh.indices.write(
             self.device,
             self.queue,
             self.mesh.offset * Indices::COUNT,
@@ -517,7 +1177,7 @@ impl Scene<'_> {
     }
 
     fn add_vertices(&mut self, vertices: Vertices) {
-        self.mesh.vertices.write(
+        self.mesh.grown |= self.mesh.vertices.write(
             self.device,
             self.queue,
             self.mesh.offset * Vertices::COUNT,
@@ -526,27 +1186,36 @@ impl Scene<'_> {
     }
 
     fn add_element(&mut self, element: Element) {
-        self.storage.elements.write(
+        let grown = self.storage.elements.write(
             self.device,
             self.queue,
             self.mesh.offset,
             &[element],
         );
+
+        if grown { self.storage_grown() }
     }
 
-    fn add_transform(&mut self, transform: &Matrix3x2) {
-        self.storage.transforms.write(
-            self.device,
-            self.queue,
-            self.mesh.offset,
-            &[transform.as_array()],
-        );
+    fn storage_grown(&mut self) {
+        self.storage.rebind(self.device);
+        self.mesh.grown = true;
+    }
+
+    /// Mark a region as changed for this frame. When any region is marked, only the union of them
+    /// gets cleared & repainted, otherwise the whole target is
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        self.damage.push(rect);
     }
 
     pub fn skip(&mut self) {
         self.mesh.offset += 1;
     }
 
+    /// The number of elements drawn so far this frame, polygons take a slot per triangle batch
+    pub fn element_count(&self) -> usize {
+        self.mesh.offset as usize
+    }
+
     pub fn size(&self) -> &Size {
         self.size
     }
@@ -617,13 +1286,788 @@ impl Pipeline {
             cache: None,
         })
     }
+
+    pub(crate) fn new_clear_pipeline(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("clear shader"),
+            source: wgpu::ShaderSource::Wgsl(crate::shader::SHADER)
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("clear pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_clear"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_clear"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                // the fragment is white, the blend constant set in the pass is the clear color
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState {
+                        color: CLEAR_BLEND,
+                        alpha: CLEAR_BLEND,
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            depth_stencil: None,
+            multiview_mask: None,
+            cache: None,
+        })
+    }
 }
 
+const CLEAR_BLEND: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::Constant,
+    dst_factor: wgpu::BlendFactor::Zero,
+    operation: wgpu::BlendOperation::Add,
+};
+
 #[inline]
-const fn backend() -> wgpu::Backends {
+pub(crate) const fn backend() -> wgpu::Backends {
     if cfg!(target_os = "macos") {
         wgpu::Backends::METAL
     } else {
         wgpu::Backends::GL
     }
 }
+
+/// A headless device for the tests, `None` when there's no adapter available
+#[cfg(test)]
+pub(crate) fn device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: backend(),
+        ..Default::default()
+    });
+
+    let adapter = aplite_future::block_on(instance.request_adapter(&Default::default())).ok()?;
+    aplite_future::block_on(adapter.request_device(&Default::default())).ok()
+}
+
+#[cfg(test)]
+mod renderer_test {
+    use aplite_types::Color;
+    use super::*;
+    use crate::buffer::{Buffer, cast_slice};
+
+    #[test]
+    fn opacity_written_into_element() {
+        let rect = Rect::new(0., 0., 100., 100.);
+        let color = Color::new(255, 0, 0, 255);
+        let paint = PaintRef::Color(&color);
+
+        let args = DrawArgs {
+            rect: &rect,
+            transform: &Matrix3x2::identity(),
+            background_paint: &paint,
+            border_paint: &paint,
+            border_width: &0.0,
+            border_style: &BorderStyle::Solid,
+            shape: &Shape::Rect,
+            corner_radius: &CornerRadius::splat(0),
+            opacity: &0.5,
+        };
+
+        let elements = [args.element(&Size::new(800., 600.))];
+        let bytes: &[u8] = cast_slice(&elements);
+        let offset = std::mem::offset_of!(Element, opacity);
+
+        assert_eq!(bytes.len(), size_of::<Element>());
+        assert_eq!(bytes[offset..offset + 4], 0.5f32.to_ne_bytes());
+    }
+
+    #[test]
+    fn border_style_written_into_element() {
+        let rect = Rect::new(0., 0., 100., 100.);
+        let color = Color::new(255, 0, 0, 255);
+        let paint = PaintRef::Color(&color);
+        let screen = Size::new(800., 600.);
+        let identity = Matrix3x2::identity();
+        let corners = CornerRadius::splat(0);
+
+        let args = |border_style| DrawArgs {
+            rect: &rect,
+            transform: &identity,
+            background_paint: &paint,
+            border_paint: &paint,
+            border_width: &2.0,
+            border_style,
+            shape: &Shape::RoundedRect,
+            corner_radius: &corners,
+            opacity: &1.0,
+        };
+
+        let solid = args(&BorderStyle::Solid).element(&screen);
+        assert_eq!(solid.border_style, 0);
+
+        let dashed = args(&BorderStyle::Dashed { dash: 8., gap: 4. }).element(&screen);
+        assert_eq!(dashed.border_style, 1);
+        assert_eq!(dashed.border_dash, [8. / 800., 8. / 600.]);
+        assert_eq!(dashed.border_gap, [4. / 800., 4. / 600.]);
+
+        let dotted = args(&BorderStyle::Dotted).element(&screen);
+        assert_eq!(dotted.border_style, 2);
+        assert_eq!(dotted.border_dash, dotted.border_gap);
+        assert_eq!(dotted.border_dash[0], dotted.border_width);
+        assert!((dotted.border_dash[1] - 2. / 600.).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn corner_radius_clamped_into_element() {
+        let rect = Rect::new(0., 0., 100., 40.);
+        let color = Color::new(255, 0, 0, 255);
+        let paint = PaintRef::Color(&color);
+        let identity = Matrix3x2::identity();
+        let corners = CornerRadius::splat(100);
+
+        let element = DrawArgs {
+            rect: &rect,
+            transform: &identity,
+            background_paint: &paint,
+            border_paint: &paint,
+            border_width: &0.0,
+            border_style: &BorderStyle::Solid,
+            shape: &Shape::RoundedRect,
+            corner_radius: &corners,
+            opacity: &1.0,
+        }.element(&Size::new(800., 600.));
+
+        assert_eq!(element.corners(), CornerRadius::splat(80));
+    }
+
+    #[test]
+    fn corner_radius_drawn_unclamped() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(256., 256.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let color = Color::new(255, 0, 0, 255);
+
+        // 60 is a radius of 30, which fits into the 80 high rect
+        renderer.begin();
+        renderer.scene().draw_rounded_rect(
+            &Rect::new(10., 10., 200., 80.),
+            &Matrix3x2::identity(),
+            &PaintRef::Color(&color),
+            &PaintRef::Color(&color),
+            &0.,
+            &CornerRadius::splat(60),
+        );
+        renderer.finish(None).unwrap();
+
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 256 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        // cut off by a radius of 30, but not by one of 20
+        assert_ne!(pixel(17, 17), [255, 0, 0, 255]);
+        assert_eq!(pixel(24, 24), [255, 0, 0, 255]);
+        assert_eq!(pixel(12, 50), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn pipeline_builds() {
+        let Some((device, _queue)) = device() else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let scope = device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let _pipeline = Renderer::render_pipeline(&device, wgpu::TextureFormat::Rgba8Unorm);
+        let error = aplite_future::block_on(scope.pop());
+
+        assert!(error.is_none(), "{error:?}");
+    }
+
+    #[test]
+    fn row_padding() {
+        assert_eq!(padded_bytes_per_row(1), 256);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+    }
+
+    #[test]
+    fn read_texture_strips_padding() {
+        let Some((device, queue)) = device() else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let (width, height) = (3, 2);
+        let data = (0..width * height * 4).map(|i| i as u8).collect::<Vec<_>>();
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        queue.write_texture(
+            texture.as_image_copy(),
+            &data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            texture.size(),
+        );
+
+        let encoder = device.create_command_encoder(&Default::default());
+        let bytes = read_texture(&device, &queue, encoder, &texture).unwrap();
+
+        assert_eq!(bytes, data);
+    }
+
+    #[test]
+    fn offscreen_draw_and_read_pixels() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let color = Color::new(200, 100, 50, 255);
+        let paint = PaintRef::Color(&color);
+
+        renderer.begin();
+        renderer.scene().draw_rect(
+            &Rect::new(16., 12., 32., 24.),
+            &Matrix3x2::identity(),
+            &paint,
+            &paint,
+            &0.,
+        );
+        renderer.finish(None).unwrap();
+
+        let (size, pixels) = renderer.read_pixels().unwrap();
+        assert_eq!(size, Size::new(64., 48.));
+        assert_eq!(pixels.len(), 64 * 48 * 4);
+
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 64 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        assert_eq!(pixel(32, 24), [200, 100, 50, 255]);
+        assert_ne!(pixel(2, 2), [200, 100, 50, 255]);
+    }
+
+    #[test]
+    fn grow_past_initial_capacity() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let initial = renderer.storage.elements.capacity();
+        let red = Color::new(255, 0, 0, 255);
+        let blue = Color::new(0, 0, 255, 255);
+        let green = Color::new(0, 255, 0, 255);
+
+        renderer.begin();
+        let mut scene = renderer.scene();
+        scene.draw_rect(&Rect::new(0., 0., 16., 16.), &Matrix3x2::identity(), &PaintRef::Color(&red), &PaintRef::Color(&red), &0.);
+        for _ in 0..initial {
+            scene.draw_rect(&Rect::new(32., 0., 1., 1.), &Matrix3x2::identity(), &PaintRef::Color(&green), &PaintRef::Color(&green), &0.);
+        }
+        scene.draw_rect(&Rect::new(48., 32., 16., 16.), &Matrix3x2::identity(), &PaintRef::Color(&blue), &PaintRef::Color(&blue), &0.);
+        renderer.finish(None).unwrap();
+
+        assert!(renderer.storage.elements.capacity() > initial);
+        assert!(renderer.mesh.vertices.capacity() >= renderer.mesh.offset * Vertices::COUNT);
+        assert!(renderer.mesh.indices.capacity() >= renderer.mesh.offset * Indices::COUNT);
+
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 64 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        // drawn before the growth, copied into the new buffers
+        assert_eq!(pixel(8, 8), [255, 0, 0, 255]);
+        // drawn after the growth
+        assert_eq!(pixel(56, 40), [0, 0, 255, 255]);
+    }
+
+    fn read_buffer<T>(renderer: &Renderer, buffer: &Buffer<T>, len: u64) -> Vec<u8> {
+        let slice = buffer.slice(0..len);
+        let size = slice.size().get();
+
+        let staging = renderer.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = renderer.device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(slice.buffer(), 0, &staging, 0, Some(size));
+        renderer.queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        renderer.device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+        slice.get_mapped_range().to_vec()
+    }
+
+    #[test]
+    fn batch_writes_same_data() {
+        let block_on = |size| aplite_future::block_on(Renderer::new_offscreen(size));
+        let (Ok(mut single), Ok(mut batched)) = (block_on(Size::new(64., 48.)), block_on(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let red = Color::new(255, 0, 0, 255);
+        let blue = Color::new(0, 0, 255, 128);
+        let (red, blue) = (PaintRef::Color(&red), PaintRef::Color(&blue));
+        let rotate = Matrix3x2::from_rotate_rad(0.5);
+        let identity = Matrix3x2::identity();
+        let rects = [Rect::new(0., 0., 10., 10.), Rect::new(20., 10., 30., 5.), Rect::new(5., 30., 8., 8.)];
+        let triangle = Shape::Polygon(vec![Point::new(5., 0.), Point::new(0., 10.), Point::new(10., 10.)]);
+        let corners = CornerRadius::splat(2);
+
+        let args = || {
+            [
+                (&rects[0], &identity, &red, &Shape::Rect),
+                (&rects[1], &rotate, &blue, &Shape::RoundedRect),
+                (&rects[2], &identity, &red, &triangle),
+                (&rects[0], &rotate, &blue, &Shape::Circle),
+            ].map(|(rect, transform, paint, shape)| DrawArgs {
+                rect,
+                transform,
+                background_paint: paint,
+                border_paint: paint,
+                border_width: &1.,
+                border_style: &BorderStyle::Solid,
+                shape,
+                corner_radius: &corners,
+                opacity: &0.75,
+            })
+        };
+
+        single.begin();
+        let mut scene = single.scene();
+        for args in args() {
+            scene.draw(args);
+        }
+
+        batched.begin();
+        batched.scene().draw_batch(&args());
+
+        let offset = single.mesh.offset;
+        assert_eq!(offset, 4);
+        assert_eq!(batched.mesh.offset, offset);
+
+        assert_eq!(
+            read_buffer(&single, &single.mesh.indices, offset * Indices::COUNT),
+            read_buffer(&batched, &batched.mesh.indices, offset * Indices::COUNT),
+        );
+        // the padding after `Vertex::atlas` is left uninitialized
+        let vertices = |renderer: &Renderer| {
+            let mut bytes = read_buffer(renderer, &renderer.mesh.vertices, offset * Vertices::COUNT);
+            let padding = size_of::<[f32; 4]>() + size_of::<u32>() + size_of::<u8>();
+            bytes.chunks_exact_mut(size_of::<Vertex>()).for_each(|vertex| vertex[padding..].fill(0));
+            bytes
+        };
+        assert_eq!(vertices(&single), vertices(&batched));
+        assert_eq!(
+            read_buffer(&single, &single.storage.elements, offset),
+            read_buffer(&batched, &batched.storage.elements, offset),
+        );
+    }
+
+    #[test]
+    fn image_border_in_atlas() {
+        use aplite_types::ImageData;
+
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let image = ImageData::new((16, 16), &[255, 0, 0, 255].repeat(256));
+        let border = ImageData::new((16, 16), &[0, 255, 0, 255].repeat(256));
+        let color = Color::new(0, 0, 255, 255);
+
+        renderer.begin();
+        let mut scene = renderer.scene();
+        scene.draw_rect(
+            &Rect::new(0., 0., 32., 32.),
+            &Matrix3x2::identity(),
+            &PaintRef::Color(&color),
+            &PaintRef::Image(image.downgrade()),
+            &8.,
+        );
+        // the same image is only allocated once
+        scene.draw_rect(
+            &Rect::new(32., 0., 32., 32.),
+            &Matrix3x2::identity(),
+            &PaintRef::Image(image.downgrade()),
+            &PaintRef::Image(border.downgrade()),
+            &8.,
+        );
+
+        assert_eq!(renderer.texture_atlas.allocator.len(), 2);
+
+        let bytes = read_buffer(&renderer, &renderer.storage.elements, 1);
+        let offset = std::mem::offset_of!(Element, border_texture);
+        assert_eq!(bytes[offset..offset + 4], 1u32.to_ne_bytes());
+
+        let scope = renderer.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        renderer.finish(None).unwrap();
+        let error = aplite_future::block_on(scope.pop());
+        assert!(error.is_none(), "{error:?}");
+
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 64 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        // image border around a color, then around an image background
+        assert_eq!(pixel(2, 16), [255, 0, 0, 255]);
+        assert_eq!(pixel(16, 16), [0, 0, 255, 255]);
+        assert_eq!(pixel(34, 16), [0, 255, 0, 255]);
+        assert_eq!(pixel(48, 16), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn image_border_too_large_for_atlas() {
+        use aplite_types::ImageData;
+
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let width = renderer.device.limits().max_texture_dimension_2d + 1;
+        let border = ImageData::new((width, 1), &[0, 255, 0, 255].repeat(width as usize));
+        let color = Color::new(0, 0, 255, 255);
+
+        renderer.begin();
+        renderer.scene().draw_rect(
+            &Rect::new(0., 0., 32., 32.),
+            &Matrix3x2::identity(),
+            &PaintRef::Color(&color),
+            &PaintRef::Image(border.downgrade()),
+            &8.,
+        );
+
+        assert_eq!(renderer.texture_atlas.allocator.len(), 0);
+
+        let bytes = read_buffer(&renderer, &renderer.storage.elements, 1);
+        let offset = std::mem::offset_of!(Element, border_width);
+        assert_eq!(bytes[offset..offset + 4], 0f32.to_ne_bytes());
+
+        renderer.finish(None).unwrap();
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        // no border drawn over the background
+        let i = (16 * 64 + 2) * 4;
+        assert_eq!(pixels[i..i + 4], [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn dashes_same_length_on_every_edge() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(200., 100.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let background = Color::new(0, 0, 255, 255);
+        let border = Color::new(255, 0, 0, 255);
+
+        renderer.begin();
+        renderer.scene().draw(DrawArgs {
+            rect: &Rect::new(0., 0., 200., 100.),
+            transform: &Matrix3x2::identity(),
+            background_paint: &PaintRef::Color(&background),
+            border_paint: &PaintRef::Color(&border),
+            border_width: &8.,
+            border_style: &BorderStyle::Dashed { dash: 10., gap: 10. },
+            shape: &Shape::Rect,
+            corner_radius: &CornerRadius::splat(0),
+            opacity: &1.0,
+        });
+        renderer.finish(None).unwrap();
+
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        let dash = |x: usize, y: usize| pixels[(y * 200 + x) * 4] > 128;
+
+        // the same period along the wide top edge and the short left edge
+        assert!(dash(12, 1) && !dash(17, 1) && dash(22, 1) && !dash(27, 1));
+        assert!(dash(1, 17) && !dash(1, 22) && dash(1, 27) && !dash(1, 32));
+    }
+
+    #[test]
+    fn uvs_follow_atlas_growth() {
+        use aplite_types::ImageData;
+
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(96., 32.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        // only fits the first image
+        renderer.texture_atlas = Atlas::new(&renderer.device, Size::square(16.), "atlas");
+
+        let red = ImageData::new((16, 16), &[255, 0, 0, 255].repeat(256));
+        let green = ImageData::new((16, 16), &[0, 255, 0, 255].repeat(256));
+        let color = Color::new(0, 0, 255, 255);
+
+        renderer.begin();
+        let mut scene = renderer.scene();
+        scene.draw_rect(
+            &Rect::new(0., 0., 32., 32.),
+            &Matrix3x2::identity(),
+            &PaintRef::Image(red.downgrade()),
+            &PaintRef::Color(&color),
+            &0.,
+        );
+        scene.draw_rect(
+            &Rect::new(64., 0., 32., 32.),
+            &Matrix3x2::identity(),
+            &PaintRef::Color(&color),
+            &PaintRef::Image(red.downgrade()),
+            &8.,
+        );
+        // grows the atlas
+        scene.draw_rect(
+            &Rect::new(32., 0., 32., 32.),
+            &Matrix3x2::identity(),
+            &PaintRef::Image(green.downgrade()),
+            &PaintRef::Color(&color),
+            &0.,
+        );
+
+        assert_eq!(renderer.texture_atlas.allocator.bound.size(), Size::square(32.));
+
+        renderer.finish(None).unwrap();
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 96 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        // the whole first image, not a quarter of the grown atlas
+        assert_eq!(pixel(2, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(29, 29), [255, 0, 0, 255]);
+        assert_eq!(pixel(48, 16), [0, 255, 0, 255]);
+        assert_eq!(pixel(66, 29), [255, 0, 0, 255]);
+        assert_eq!(pixel(93, 29), [255, 0, 0, 255]);
+        assert_eq!(pixel(80, 16), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn logical_physical_round_trip() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        renderer.set_scale_factor(2., |_| {});
+
+        let logical = Point::new(12.5, 30.);
+        let physical = renderer.logical_to_physical(logical);
+
+        assert_eq!(physical, Point::new(25., 60.));
+        assert_eq!(renderer.physical_to_logical(physical), logical);
+    }
+
+    #[test]
+    fn scale_factor_rewrites_screen_transform() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let mut logical = Size::default();
+        renderer.set_scale_factor(2., |size| logical = size);
+        assert_eq!(logical, Size::new(32., 24.));
+
+        let expected = [Matrix3x2::from_scale_translate(2., 2., 1., -1.)];
+        let written = read_buffer(&renderer, &renderer.screen.transform, 1);
+        assert_eq!(written, cast_slice::<_, u8>(&expected));
+    }
+
+    #[test]
+    fn damage_union_of_marked_rects() {
+        assert_eq!(damage_union(&[]), None);
+
+        let rects = [
+            Rect::new(10., 10., 20., 20.),
+            Rect::new(50., 5., 10., 10.),
+            Rect::new(0., 40., 5., 5.),
+        ];
+
+        assert_eq!(damage_union(&rects[..1]), Some(rects[0]));
+        assert_eq!(damage_union(&rects), Some(Rect::new(0., 5., 60., 40.)));
+    }
+
+    #[test]
+    fn partial_redraw_keeps_undamaged_pixels() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let red = Color::new(255, 0, 0, 255);
+        let blue = Color::new(0, 0, 255, 255);
+        let full = Rect::new(0., 0., 64., 48.);
+
+        renderer.begin();
+        renderer.scene().draw_rect(&full, &Matrix3x2::identity(), &PaintRef::Color(&red), &PaintRef::Color(&red), &0.);
+        renderer.finish(None).unwrap();
+
+        renderer.begin();
+        let mut scene = renderer.scene();
+        scene.draw_rect(&full, &Matrix3x2::identity(), &PaintRef::Color(&blue), &PaintRef::Color(&blue), &0.);
+        scene.mark_dirty(Rect::new(0., 0., 32., 48.));
+        renderer.finish(None).unwrap();
+
+        let RenderTarget::Offscreen(texture) = &renderer.target else { unreachable!() };
+        let encoder = renderer.device.create_command_encoder(&Default::default());
+        let pixels = read_texture(&renderer.device, &renderer.queue, encoder, texture).unwrap();
+
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 64 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        assert_eq!(pixel(8, 24), [0, 0, 255, 255]);
+        assert_eq!(pixel(56, 24), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn clear_color_fills_target() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let green = Color::new(0, 255, 0, 255);
+        renderer.set_clear_color(green);
+
+        renderer.begin();
+        let (_, pixels) = renderer.read_pixels().unwrap();
+        assert!(pixels.chunks_exact(4).all(|pixel| pixel == [0, 255, 0, 255]));
+
+        // the damaged region is cleared with the same color
+        let red = Color::new(255, 0, 0, 255);
+        let full = Rect::new(0., 0., 64., 48.);
+
+        renderer.scene().draw_rect(&full, &Matrix3x2::identity(), &PaintRef::Color(&red), &PaintRef::Color(&red), &0.);
+        renderer.finish(None).unwrap();
+
+        renderer.begin();
+        let mut scene = renderer.scene();
+        scene.draw_rect(&Rect::new(0., 0., 8., 8.), &Matrix3x2::identity(), &PaintRef::Color(&red), &PaintRef::Color(&red), &0.);
+        scene.mark_dirty(Rect::new(0., 0., 32., 48.));
+        renderer.finish(None).unwrap();
+
+        let RenderTarget::Offscreen(texture) = &renderer.target else { unreachable!() };
+        let encoder = renderer.device.create_command_encoder(&Default::default());
+        let pixels = read_texture(&renderer.device, &renderer.queue, encoder, texture).unwrap();
+
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 64 + x) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+
+        assert_eq!(pixel(4, 4), [255, 0, 0, 255]);
+        assert_eq!(pixel(16, 24), [0, 255, 0, 255]);
+        assert_eq!(pixel(56, 24), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn debug_overlay_outlines_every_element() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let red = Color::new(255, 0, 0, 255);
+        let paint = PaintRef::Color(&red);
+
+        let draw = |renderer: &mut Renderer| {
+            renderer.begin();
+            let mut scene = renderer.scene();
+            scene.draw_rect(&Rect::new(0., 0., 16., 16.), &Matrix3x2::identity(), &paint, &paint, &0.);
+            scene.draw_circle(&Rect::new(20., 0., 16., 16.), &Matrix3x2::identity(), &paint, &paint, &0.);
+            scene.draw_rect(&Rect::new(40., 20., 16., 16.), &Matrix3x2::identity(), &paint, &paint, &0.);
+            renderer.finish(None).unwrap();
+            renderer.mesh.offset
+        };
+
+        assert_eq!(draw(&mut renderer), 3);
+
+        renderer.set_debug_overlay(true);
+        assert_eq!(draw(&mut renderer), 6);
+        assert_eq!(draw(&mut renderer), 6);
+
+        renderer.set_debug_overlay(false);
+        assert_eq!(draw(&mut renderer), 3);
+    }
+
+    #[test]
+    fn element_count() {
+        let Ok(mut renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let red = Color::new(255, 0, 0, 255);
+        let paint = PaintRef::Color(&red);
+
+        renderer.begin();
+        let mut scene = renderer.scene();
+        assert_eq!(scene.element_count(), 0);
+
+        for i in 0..5 {
+            scene.draw_rect(&Rect::new(i as f32 * 10., 0., 8., 8.), &Matrix3x2::identity(), &paint, &paint, &0.);
+        }
+        assert_eq!(scene.element_count(), 5);
+
+        renderer.finish(None).unwrap();
+        assert_eq!(renderer.element_count(), 5);
+
+        // an empty frame returns early
+        renderer.begin();
+        renderer.finish(None).unwrap();
+        assert_eq!(renderer.element_count(), 0);
+    }
+
+    #[test]
+    fn capabilities() {
+        let Ok(renderer) = aplite_future::block_on(Renderer::new_offscreen(Size::new(64., 48.))) else {
+            eprintln!("no adapter available, skipping");
+            return;
+        };
+
+        let caps = renderer.capabilities();
+        assert!(caps.max_texture_dimension > 0);
+        assert!(caps.max_buffer_size > 0);
+        assert!(caps.sample_counts.contains(&1));
+        assert!(caps.present_modes.is_empty());
+    }
+}
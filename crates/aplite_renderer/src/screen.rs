@@ -1,4 +1,4 @@
-use aplite_types::{Matrix3x2, Size};
+use aplite_types::{Matrix3x2, Point, Size};
 
 use super::buffer::Buffer;
 
@@ -31,6 +31,18 @@ impl Screen {
         }
     }
 
+    /// Logical coordinates, as used by the layout, into physical pixels
+    pub(crate) fn logical_to_physical(&self, point: Point) -> Point {
+        let scale = self.scale_factor as f32;
+        Point::new(point.x * scale, point.y * scale)
+    }
+
+    /// Physical pixels, as reported by winit, into logical coordinates
+    pub(crate) fn physical_to_logical(&self, point: Point) -> Point {
+        let scale = self.scale_factor as f32;
+        Point::new(point.x / scale, point.y / scale)
+    }
+
     pub(crate) fn write(
         &mut self,
         device: &wgpu::Device,
@@ -1,7 +1,15 @@
 pub const SHADER: std::borrow::Cow<'_, str> = std::borrow::Cow::Borrowed(SDF_SHADER);
 
 pub const SDF_SHADER: &str = r"
-@group(0) @binding(0) var<uniform> screen_t: mat3x2f;
+// a bare mat3x2f uniform gets its columns padded to 16 bytes on gl (std140),
+// while vec2f struct members keep the same 8 bytes stride everywhere
+struct ScreenTransform {
+    x: vec2f,
+    y: vec2f,
+    translate: vec2f,
+}
+
+@group(0) @binding(0) var<uniform> screen_t: ScreenTransform;
 
 struct Corners {
     top_left: f32,
@@ -44,22 +52,24 @@ struct Element {
     corners: u32,
     shape: u32,
     border_width: f32,
+    opacity: f32,
+    border_texture: u32,
+    border_uv: array<f32, 4>,
+    border_style: u32,
+    border_dash: array<f32, 2>,
+    border_gap: array<f32, 2>,
 }
 
 @group(1) @binding(0) var<storage> elements: array<Element>;
-@group(1) @binding(1) var<storage> transforms: array<mat3x2<f32>>;
-
-// scale -> rotate -> translate
-fn transform_point(tx: mat3x2<f32>, pos: vec2<f32>) -> vec2f {
-    let r = mat2x2<f32>(tx[0], tx[1]);
-
-    let s = mat2x2<f32>(screen_t[0], screen_t[1]);
 
-    return s * r * pos + tx[2] + screen_t[2];
-    // return s * pos + screen_t[2];
+// element transforms are applied to the vertices on the cpu side
+fn transform_point(pos: vec2<f32>) -> vec2f {
+    let s = mat2x2<f32>(screen_t.x, screen_t.y);
+    return s * pos + screen_t.translate;
 }
 
 struct VertexInput {
+    @builtin(vertex_index) index: u32,
     @location(0) pos: vec2<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) id: u32,
@@ -71,20 +81,26 @@ struct FragmentPayload {
     @location(0) uv: vec2<f32>,
     @location(1) @interpolate(flat) index: u32,
     @location(2) @interpolate(flat) atlas: u32,
+    // -1..1 across the quad, the uv is the atlas region on textured quads
+    @location(3) local: vec2<f32>,
+}
+
+// quad vertices are laid out top-left, bottom-left, bottom-right, top-right
+fn quad_corner(index: u32) -> vec2f {
+    var corners = array<vec2f, 4>(vec2f(0., 0.), vec2f(0., 1.), vec2f(1., 1.), vec2f(1., 0.));
+    return corners[index % 4u];
 }
 
 @vertex
 fn vs_main(vertex: VertexInput) -> FragmentPayload {
-    let element = elements[vertex.id];
-    let transform = transforms[vertex.id];
-
-    let pos = transform_point(transform, vertex.pos);
+    let pos = transform_point(vertex.pos);
 
     var out: FragmentPayload;
     out.position = vec4f(pos, 0.0, 1.0);
     out.uv = select(vertex.uv * 2 - 1, vertex.uv, vertex.atlas == 1);
     out.index = vertex.id;
     out.atlas = vertex.atlas;
+    out.local = select(vertex.uv, quad_corner(vertex.index), vertex.atlas == 1) * 2 - 1;
     return out;
 }
 
@@ -114,6 +130,27 @@ fn sdSegment(p: vec2f, a: vec2f, b: vec2f) -> f32 {
     return length(pa - ba * h);
 }
 
+fn sdTriangle(p: vec2f, p0: vec2f, p1: vec2f, p2: vec2f) -> f32 {
+    let e0 = p1 - p0;
+    let e1 = p2 - p1;
+    let e2 = p0 - p2;
+    let v0 = p - p0;
+    let v1 = p - p1;
+    let v2 = p - p2;
+    let pq0 = v0 - e0 * clamp(dot(v0, e0) / dot(e0, e0), 0., 1.);
+    let pq1 = v1 - e1 * clamp(dot(v1, e1) / dot(e1, e1), 0., 1.);
+    let pq2 = v2 - e2 * clamp(dot(v2, e2) / dot(e2, e2), 0., 1.);
+    let s = sign(e0.x * e2.y - e0.y * e2.x);
+    let d = min(
+        min(
+            vec2f(dot(pq0, pq0), s * (v0.x * e0.y - v0.y * e0.x)),
+            vec2f(dot(pq1, pq1), s * (v1.x * e1.y - v1.y * e1.x))
+        ),
+        vec2f(dot(pq2, pq2), s * (v2.x * e2.y - v2.y * e2.x))
+    );
+    return -sqrt(d.x) * sign(d.y);
+}
+
 fn sdf(uv: vec2<f32>, element: Element) -> f32 {
     let border_width = element.border_width;
     let size = vec2f(element.width, element.height);
@@ -135,6 +172,11 @@ fn sdf(uv: vec2<f32>, element: Element) -> f32 {
             let r = unpack_corners(element.corners, size.x);
             return sdRoundedRect(p, b, r);
         }
+        case 3u: {
+            let p = uv * size;
+            let b = size - border_width;
+            return sdTriangle(p, vec2f(0., -b.y), vec2f(-b.x, b.y), vec2f(b.x, b.y));
+        }
         case 4u: {
             let p = uv * size.x;
             let b = size - border_width;
@@ -144,6 +186,33 @@ fn sdf(uv: vec2<f32>, element: Element) -> f32 {
     }
 }
 
+// smooth coverage for both the fill and the border, fw is the screen space derivative of the uv
+fn circle_color(uv: vec2f, fw: f32, element: Element, background: vec4f, border: vec4f) -> vec4f {
+    let r = element.width;
+    let outer = sdCircle(uv * r, r);
+    let inner = outer + element.border_width;
+    let aa = fw * r * 0.5;
+
+    let fill = 1.0 - smoothstep(-aa, aa, inner);
+    let coverage = 1.0 - smoothstep(-aa, aa, outer);
+
+    var color = mix(border, background, fill);
+    color.a *= coverage;
+    return color;
+}
+
+// full screen triangle used to clear the damaged region, the scissor rect does the clipping
+@vertex
+fn vs_clear(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    let uv = vec2f(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_clear() -> @location(0) vec4f {
+    return vec4f(1.0);
+}
+
 @group(2) @binding(0) var atlas: texture_2d<f32>;
 @group(2) @binding(1) var glyph: texture_2d<f32>;
 @group(3) @binding(0) var s: sampler;
@@ -156,28 +225,116 @@ fn toLinear(input: f32) -> f32 {
     return pow((input + 0.055) / 1.055, 2.4);
 }
 
+// the uv of an untextured quad spans -1..1, remapped into the border image region of the atlas
+fn border_color(uv: vec2f, element: Element) -> vec4f {
+    if element.border_texture == 0 {
+        return unpack_color(element.border);
+    }
+
+    let local = (uv + 1.0) / 2.0;
+    let min = vec2f(element.border_uv[0], element.border_uv[1]);
+    let max = vec2f(element.border_uv[2], element.border_uv[3]);
+    return textureSampleLevel(atlas, s, mix(min, max, local), 0.0);
+}
+
+// pattern periods travelled clockwise along the border from the top-left, p is relative to the center.
+// the horizontal edges are measured with the period along x, the vertical ones with the period along y
+fn border_phase(p: vec2f, size: vec2f, period: vec2f, shape: u32) -> f32 {
+    if shape == 0 {
+        return (atan2(p.y, p.x) + 3.14159265) * size.x / period.x;
+    }
+
+    let w = size.x * 2.0 / period.x;
+    let h = size.y * 2.0 / period.y;
+
+    if abs(p.y) / size.y > abs(p.x) / size.x {
+        return select(w + h + (size.x - p.x) / period.x, (p.x + size.x) / period.x, p.y < 0.0);
+    }
+
+    return select(w * 2.0 + h + (size.y - p.y) / period.y, w + (p.y + size.y) / period.y, p.x > 0.0);
+}
+
+// 1 on the dashes, 0 in the gaps in between
+fn border_pattern(uv: vec2f, element: Element) -> f32 {
+    if element.border_style == 0 || element.border_dash[0] <= 0.0 {
+        return 1.0;
+    }
+
+    let size = vec2f(element.width, element.height);
+    let p = select(uv * size, uv * size.x, element.shape == 0);
+    let dash = vec2f(element.border_dash[0], element.border_dash[1]);
+    let period = dash + vec2f(element.border_gap[0], element.border_gap[1]);
+    let along = fract(border_phase(p, size, period, element.shape));
+    return select(0.0, 1.0, along < dash.x / period.x);
+}
+
+fn pattern_border_color(uv: vec2f, element: Element) -> vec4f {
+    var color = border_color(uv, element);
+    color.a *= border_pattern(uv, element);
+    return color;
+}
+
 @fragment
 fn fs_main(in: FragmentPayload) -> @location(0) vec4<f32> {
     let element = elements[in.index];
     let background_color = unpack_color(element.background);
 
-    let sdf = sdf(in.uv, element);
-    let fw = length(fwidth(in.uv));
-    let blend = 1.0 - smoothstep(-fw/2.0, fw/2.0, sdf);
-
     if element.shape == 4 {
+        let sdf = sdf(in.uv, element);
+        let fw = length(fwidth(in.uv));
+        let blend = 1.0 - smoothstep(-fw/2.0, fw/2.0, sdf);
         let a = textureSample(glyph, s, in.uv).r;
         var color = vec4f(background_color.rgb, toLinear(a));
-        color.a *= blend;
+        color.a *= blend * element.opacity;
         return color;
     }
 
-    if in.atlas == 1 {
-        return textureSample(atlas, s, in.uv);
+    // polygons are triangulated on the cpu side, there's no sdf to blend the border with
+    if element.shape == 5 {
+        var color = background_color;
+        color.a *= element.opacity;
+        return color;
     }
 
-    let color = select(vec4f(0.0), background_color, sdf < 0.0);
-    let border_color = unpack_color(element.border);
-    return mix(color, border_color, blend);
+    // the image takes the place of the background color, the border is mixed in the same way
+    let image = textureSampleLevel(atlas, s, in.uv, 0.0);
+    let background = select(background_color, image, in.atlas == 1);
+    let border = pattern_border_color(in.local, element);
+    let fw = length(fwidth(in.local));
+
+    if element.shape == 0 {
+        var color = circle_color(in.local, fw, element, background, border);
+        color.a *= element.opacity;
+        return color;
+    }
+
+    // the sdf is the distance to the inner edge of the border, the outer edge is border width further
+    let inner = sdf(in.local, element);
+    let outer = inner - element.border_width;
+    let aa = fw * element.width * 0.5;
+
+    let fill = 1.0 - smoothstep(-aa, aa, inner);
+    let coverage = 1.0 - smoothstep(-aa, aa, outer);
+
+    var out = mix(border, background, fill);
+    out.a *= coverage * element.opacity;
+    return out;
 }
 ";
+
+#[cfg(test)]
+mod shader_test {
+    use wgpu::naga;
+    use super::SDF_SHADER;
+
+    #[test]
+    fn sdf_shader_validates() {
+        let module = naga::front::wgsl::parse_str(SDF_SHADER).unwrap();
+        let result = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        ).validate(&module);
+
+        assert!(result.is_ok(), "{result:?}");
+    }
+}
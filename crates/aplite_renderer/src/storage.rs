@@ -3,7 +3,6 @@ use super::buffer::Buffer;
 
 pub(crate) struct StorageBuffers {
     pub(crate) elements: Buffer<Element>,
-    pub(crate) transforms: Buffer<[f32; 6]>,
     pub(crate) bind_group: wgpu::BindGroup,
 }
 
@@ -11,27 +10,26 @@ impl StorageBuffers {
     pub(crate) fn new(device: &wgpu::Device) -> Self {
         let storage = wgpu::BufferUsages::STORAGE;
         let elements = Buffer::<Element>::new(device, 1024, storage);
-        let transforms = Buffer::<[f32; 6]>::new(device, 1024, storage);
 
-        let bind_group = Self::bind_group(device, &[
-            elements.bind_group_entry(0),
-            transforms.bind_group_entry(1),
-        ]);
+        let bind_group = Self::bind_group(device, &[elements.bind_group_entry(0)]);
 
         Self {
             elements,
-            transforms,
             bind_group,
         }
     }
 
+    /// Recreate the bind group after the buffer grew
+    pub(crate) fn rebind(&mut self, device: &wgpu::Device) {
+        self.bind_group = Self::bind_group(device, &[self.elements.bind_group_entry(0)]);
+    }
+
     pub(crate) fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
         let binding_type = wgpu::BufferBindingType::Storage { read_only: true };
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("gfx bind group layout"),
             entries: &[
                 Buffer::<Element>::bind_group_layout_entry(binding_type, 0),
-                Buffer::<[f32; 6]>::bind_group_layout_entry(binding_type, 1),
             ],
         })
     }
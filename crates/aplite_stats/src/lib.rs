@@ -4,6 +4,8 @@ pub struct Stats {
     startup_time: std::time::Duration,
     longest: std::time::Duration,
     shortest: std::time::Duration,
+    elements: usize,
+    most_elements: usize,
 }
 
 impl Stats {
@@ -14,6 +16,8 @@ impl Stats {
             startup_time: std::time::Duration::from_nanos(0),
             longest: std::time::Duration::from_nanos(0),
             shortest: std::time::Duration::from_nanos(0),
+            elements: 0,
+            most_elements: 0,
         }
     }
 
@@ -34,6 +38,12 @@ impl Stats {
         if self.counter > 0 { self.fps += fps }
         self.counter += 1;
     }
+
+    /// Record the number of elements drawn by the frame, call it along with [`inc`](Self::inc)
+    pub fn elements(&mut self, count: usize) {
+        self.elements += count;
+        self.most_elements = self.most_elements.max(count);
+    }
 }
 
 impl Drop for Stats {
@@ -52,6 +62,8 @@ impl Drop for Stats {
             eprintln!(" > avg fps:            {fps}");
             eprintln!("   - hi:               {:?}", self.longest);
             eprintln!("   + lo:               {:?}", self.shortest);
+            eprintln!(" > avg elements:       {}", self.elements / self.counter as usize);
+            eprintln!("   - most:             {}", self.most_elements);
         }
     }
 }
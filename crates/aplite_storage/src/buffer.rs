@@ -65,13 +65,20 @@ impl TypeErasedBuffer {
         }
     }
 
+    /// View the elements as a contiguous slice.
+    /// `T` has to be the type the buffer was created with, a different layout panics in debug builds
     pub fn as_slice<T>(&self) -> &[T] {
+        debug_assert_eq!(alloc::Layout::new::<T>(), self.item_layout, "buffer read as a different type");
+
         unsafe {
             std::slice::from_raw_parts(self.raw.cast::<T>().cast_const(), self.len)
         }
     }
 
-    pub fn as_slice_mut<T>(&mut self) -> &mut[T] {
+    /// Same as [`as_slice`](Self::as_slice), but mutable
+    pub fn as_mut_slice<T>(&mut self) -> &mut [T] {
+        debug_assert_eq!(alloc::Layout::new::<T>(), self.item_layout, "buffer read as a different type");
+
         unsafe {
             std::slice::from_raw_parts_mut(self.raw.cast::<T>(), self.len)
         }
@@ -212,6 +219,16 @@ impl TypeErasedBuffer {
         None
     }
 
+    /// Swap two live elements in place, nothing is dropped.
+    /// # Panics
+    /// If either `a` or `b` is out of bounds
+    pub fn swap<T>(&mut self, a: usize, b: usize) {
+        assert!(a < self.len && b < self.len, "swap index out of bounds: {a}, {b}, the len is {}", self.len);
+        debug_assert_eq!(alloc::Layout::new::<T>(), self.item_layout);
+
+        self.as_mut_slice::<T>().swap(a, b);
+    }
+
     pub fn iter<'a, T>(&'a self) -> Iter<'a, T> {
         unsafe { Iter::new(self.raw.cast::<T>(), self.len()) }
     }
@@ -757,6 +774,44 @@ mod buffer_test {
         assert!(removed.age == to_remove as _);
     }
 
+    #[test]
+    fn swap() {
+        let mut buffer = TypeErasedBuffer::with_capacity::<Obj>(5);
+
+        for i in 0..5 {
+            buffer.push(Obj { name: i.to_string(), age: i as _ });
+        }
+
+        buffer.swap::<Obj>(1, 3);
+        let ages = buffer.iter::<Obj>().map(|obj| obj.age).collect::<Vec<_>>();
+        assert_eq!(ages, [0, 3, 2, 1, 4]);
+        assert_eq!(buffer.get::<Obj>(1).unwrap().name, "3");
+
+        buffer.swap::<Obj>(2, 2);
+        assert_eq!(buffer.get::<Obj>(2).unwrap().age, 2);
+    }
+
+    #[test]
+    fn slices() {
+        let mut buffer = TypeErasedBuffer::new::<u32>();
+        buffer.extend(4, [1u32, 2, 3, 4]);
+
+        assert_eq!(buffer.as_slice::<u32>().iter().sum::<u32>(), 10);
+
+        buffer.as_mut_slice::<u32>().iter_mut().for_each(|num| *num *= 10);
+        buffer.as_mut_slice::<u32>().reverse();
+        assert_eq!(buffer.as_slice::<u32>(), [40, 30, 20, 10]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_out_of_bounds() {
+        let mut buffer = TypeErasedBuffer::with_capacity::<u32>(2);
+        buffer.push(1u32);
+        buffer.push(2u32);
+        buffer.swap::<u32>(0, 2);
+    }
+
     #[test]
     fn zst() {
         const CAP: usize = 10;
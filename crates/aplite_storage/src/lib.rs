@@ -11,6 +11,7 @@ pub use tree::{
 
 pub use map::{
     id::SlotId,
+    hash::{SlotIdMap, TypeIdMap},
     slot_map::{SlotMap, Error}
 };
 
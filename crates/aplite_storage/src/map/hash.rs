@@ -0,0 +1,181 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::collections::hash_map::{Entry, Iter, IterMut};
+
+use rustc_hash::FxBuildHasher;
+
+use super::id::SlotId;
+
+/*
+#########################################################
+#
+# Maps
+#
+#########################################################
+*/
+
+macro_rules! id_map {
+    ($name:ident, $key:ty) => {
+        /// Hashed with FxHash, the keys hash themselves by writing a single integer
+        /// and don't need the DoS resistance of the default SipHash
+        #[derive(Debug, Clone)]
+        pub struct $name<V>(HashMap<$key, V, FxBuildHasher>);
+
+        impl<V> Default for $name<V> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<V> $name<V> {
+            pub fn new() -> Self {
+                Self(HashMap::default())
+            }
+
+            pub fn with_capacity(capacity: usize) -> Self {
+                Self(HashMap::with_capacity_and_hasher(capacity, FxBuildHasher))
+            }
+
+            pub fn reserve(&mut self, additional: usize) {
+                self.0.reserve(additional);
+            }
+
+            /// Get or insert in a single lookup
+            pub fn entry(&mut self, key: $key) -> Entry<'_, $key, V> {
+                self.0.entry(key)
+            }
+
+            pub fn insert(&mut self, key: $key, value: V) -> Option<V> {
+                self.0.insert(key, value)
+            }
+
+            pub fn get(&self, key: &$key) -> Option<&V> {
+                self.0.get(key)
+            }
+
+            pub fn get_mut(&mut self, key: &$key) -> Option<&mut V> {
+                self.0.get_mut(key)
+            }
+
+            pub fn remove(&mut self, key: &$key) -> Option<V> {
+                self.0.remove(key)
+            }
+
+            pub fn contains_key(&self, key: &$key) -> bool {
+                self.0.contains_key(key)
+            }
+
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            pub fn capacity(&self) -> usize {
+                self.0.capacity()
+            }
+
+            pub fn clear(&mut self) {
+                self.0.clear();
+            }
+
+            pub fn iter(&self) -> Iter<'_, $key, V> {
+                self.0.iter()
+            }
+
+            pub fn iter_mut(&mut self) -> IterMut<'_, $key, V> {
+                self.0.iter_mut()
+            }
+        }
+    };
+}
+
+id_map!(SlotIdMap, SlotId);
+id_map!(TypeIdMap, TypeId);
+
+#[cfg(test)]
+mod hash_test {
+    use super::*;
+
+    use std::hash::{BuildHasher, RandomState};
+    use std::hint::black_box;
+    use std::time::{Duration, Instant};
+
+    fn hashing_cost(build: &impl BuildHasher, keys: &[SlotId]) -> Duration {
+        (0..5).map(|_| {
+            let start = Instant::now();
+            keys.iter().for_each(|key| { black_box(build.hash_one(black_box(key))); });
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+    }
+
+    // wall clock timing is flaky on a loaded machine, run with `cargo test -- --ignored`
+    #[test]
+    #[ignore]
+    fn cheaper_than_sip_hash() {
+        let keys = (0..100_000).map(|i| SlotId::new(i, i % 7)).collect::<Vec<_>>();
+
+        let fx = hashing_cost(&FxBuildHasher, &keys);
+        let sip = hashing_cost(&RandomState::new(), &keys);
+
+        assert!(fx < sip, "FxHash: {fx:?}, SipHash: {sip:?}");
+    }
+
+    #[test]
+    fn lookups() {
+        let mut map = SlotIdMap::new();
+        for index in 0..1000 {
+            for version in 0..4 {
+                map.insert(SlotId::new(index, version), (index, version));
+            }
+        }
+
+        assert_eq!(map.len(), 4000);
+        for index in 0..1000 {
+            for version in 0..4 {
+                assert_eq!(map.get(&SlotId::new(index, version)), Some(&(index, version)));
+            }
+        }
+        assert!(map.get(&SlotId::new(1000, 0)).is_none());
+        assert!(map.get(&SlotId::new(0, 4)).is_none());
+
+        let mut types = TypeIdMap::new();
+        types.insert(TypeId::of::<u8>(), "u8");
+        types.insert(TypeId::of::<u16>(), "u16");
+        types.insert(TypeId::of::<SlotId>(), "SlotId");
+        assert_eq!(types.get(&TypeId::of::<u16>()), Some(&"u16"));
+        assert_eq!(types.get(&TypeId::of::<SlotId>()), Some(&"SlotId"));
+        assert!(types.get(&TypeId::of::<u32>()).is_none());
+    }
+
+    #[test]
+    fn entry_or_insert_with() {
+        let mut map = SlotIdMap::with_capacity(16);
+        assert!(map.capacity() >= 16);
+
+        let id = SlotId::new(3, 1);
+        let mut calls = 0;
+
+        let value = *map.entry(id).or_insert_with(|| { calls += 1; 69 });
+        assert_eq!(value, 69);
+
+        let value = *map.entry(id).or_insert_with(|| { calls += 1; 420 });
+        assert_eq!(value, 69);
+        assert_eq!(calls, 1);
+        assert_eq!(map.len(), 1);
+
+        // same index, different version is another key
+        map.entry(SlotId::new(3, 2)).or_insert(420);
+        assert_eq!(map.len(), 2);
+
+        let mut types = TypeIdMap::new();
+        types.entry(TypeId::of::<u32>()).or_insert_with(Vec::new).push(1);
+        types.entry(TypeId::of::<u32>()).or_insert_with(Vec::new).push(2);
+        assert_eq!(types.get(&TypeId::of::<u32>()), Some(&vec![1, 2]));
+        assert!(!types.contains_key(&TypeId::of::<u64>()));
+    }
+}
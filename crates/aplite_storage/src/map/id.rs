@@ -20,6 +20,13 @@ impl SlotId {
         self.index as _
     }
 
+    /// Same index with another version, e.g. to rebump a handle of a recycled slot
+    pub const fn with_version(&self, version: u32) -> Self {
+        Self::new(self.index, version)
+    }
+
+    /// The version in the high 32 bits and the index in the low 32 bits.
+    /// Both are u32, so the max index `u32::MAX` can't spill into the version
     pub const fn raw(&self) -> u64 {
         (self.version as u64) << 32 | self.index as u64
     }
@@ -36,3 +43,31 @@ impl std::fmt::Debug for SlotId {
         write!(f, "SlotId({})", self.index)
     }
 }
+
+#[cfg(test)]
+mod id_test {
+    use super::*;
+
+    #[test]
+    fn raw_at_boundary() {
+        let id = SlotId::new(u32::MAX, 1);
+        let raw = id.raw();
+
+        assert_eq!(raw as u32, u32::MAX);
+        assert_eq!((raw >> 32) as u32, 1);
+
+        let id = SlotId::new(u32::MAX - 1, u32::MAX);
+        let raw = id.raw();
+        assert_eq!(SlotId::new(raw as u32, (raw >> 32) as u32), id);
+    }
+
+    #[test]
+    fn with_version() {
+        let id = SlotId::new(7, 2);
+        let bumped = id.with_version(4);
+
+        assert_eq!(bumped.index(), id.index());
+        assert_eq!(bumped.version(), 4);
+        assert_ne!(bumped, id);
+    }
+}
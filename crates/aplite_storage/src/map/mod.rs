@@ -1,3 +1,4 @@
+pub(crate) mod hash;
 pub(crate) mod slot_map;
 pub(crate) mod slot;
 pub(crate) mod id;
@@ -93,9 +93,9 @@ impl<T> SlotMap<T> {
             // after removal
             Some(slot) => unsafe {
                 let next_id = slot.content.next_id;
+                slot.occupy(data);
                 let id = SlotId::new(self.next, slot.version);
 
-                slot.occupy(data);
                 self.next = next_id;
                 self.count += 1;
 
@@ -176,6 +176,15 @@ impl<T> SlotMap<T> {
             .is_some_and(|slot| slot.validate_occupied(index.version))
     }
 
+    /// The key currently owning the value stored at `index`, with the version of the live value.
+    /// Values are stored in place of their slot, so no reverse lookup table is needed
+    pub fn key_at_index(&self, index: usize) -> Option<SlotId> {
+        self.inner
+            .get(index)
+            .filter(|slot| !slot.is_empty())
+            .map(|slot| SlotId::new(index as _, slot.version))
+    }
+
     pub fn len(&self) -> usize { self.count as usize }
 
     pub fn is_empty(&self) -> bool { self.count == 0 }
@@ -186,6 +195,31 @@ impl<T> SlotMap<T> {
         self.count = 0;
     }
 
+    /// Remove and yield every live value. Unlike [`clear`](SlotMap::clear) the slots are kept with bumped versions,
+    /// so the drained [`SlotId`]s become stale instead of resolving to values inserted later
+    pub fn drain(&mut self) -> impl Iterator<Item = (SlotId, T)> + use<T> {
+        let drained = self.inner
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, slot)| {
+                let next_id = i as u32 + 1;
+
+                if slot.is_empty() {
+                    slot.content.next_id = next_id;
+                    None
+                } else {
+                    let id = SlotId::new(i as _, slot.version);
+                    Some((id, slot.set_vacant(next_id)))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.next = 0;
+        self.count = 0;
+
+        drained.into_iter()
+    }
+
     pub fn iter(&self) -> Iter<'_, T> {
         let inner = self
             .inner
@@ -207,6 +241,15 @@ impl<T> SlotMap<T> {
     }
 }
 
+impl<T> IntoIterator for SlotMap<T> {
+    type Item = (SlotId, T);
+    type IntoIter = std::vec::IntoIter<(SlotId, T)>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.drain().collect::<Vec<_>>().into_iter()
+    }
+}
+
 /// It's important to return the data here, in case non-copy data is being used and data is needed in error handling
 pub enum Error<T> {
     ReachedMaxCapacity(T),
@@ -312,6 +355,52 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
 mod slot_map_test {
     use super::*;
 
+    #[test]
+    fn drain() {
+        let mut storage = SlotMap::new();
+        let ids = (0..5).map(|i| storage.insert(i.to_string())).collect::<Vec<_>>();
+        storage.remove(ids[1]);
+
+        let drained = storage.drain().collect::<Vec<_>>();
+        assert_eq!(drained.len(), 4);
+        assert!(drained.iter().all(|(id, val)| *val == ids.iter().position(|i| i == id).unwrap().to_string()));
+
+        assert!(storage.is_empty());
+        assert_eq!(storage.iter().count(), 0);
+        assert!(ids.iter().all(|id| storage.get(id).is_none()));
+
+        // the slots are reused, the drained handles stay stale
+        let new_ids = (0..6).map(|i| storage.insert(i.to_string())).collect::<Vec<_>>();
+        assert_eq!(storage.len(), 6);
+        assert!(ids.iter().all(|id| storage.get(id).is_none()));
+        assert!(new_ids.iter().all(|id| storage.get(id).is_some()));
+
+        let owned = storage.into_iter().map(|(_, val)| val).collect::<Vec<_>>();
+        assert_eq!(owned.len(), 6);
+    }
+
+    #[test]
+    fn key_at_index() {
+        let mut storage = SlotMap::new();
+        let ids = (0..5).map(|i| storage.insert(i)).collect::<Vec<_>>();
+
+        storage.remove(ids[2]);
+        assert!(storage.key_at_index(2).is_none());
+        assert!(storage.key_at_index(5).is_none());
+
+        for id in ids.iter().filter(|id| **id != ids[2]) {
+            assert_eq!(storage.key_at_index(id.index()), Some(*id));
+        }
+
+        // the reused slot resolves to the new version
+        let reused = storage.insert(69);
+        assert_eq!(reused.index(), 2);
+        assert_ne!(reused, ids[2]);
+        assert_eq!(storage.key_at_index(2), Some(reused));
+        assert_eq!(storage.get(&reused), Some(&69));
+        assert_eq!(storage[storage.key_at_index(2).unwrap()], 69);
+    }
+
     #[test]
     fn insert_get() {
         let mut storage = SlotMap::new();
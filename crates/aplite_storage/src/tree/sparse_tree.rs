@@ -193,24 +193,50 @@ impl SparseTree {
 
     #[inline(always)]
     pub fn detach(&mut self, id: SlotId) {
-        let prev = self.get_prev_sibling(id);
-        let next = self.get_next_sibling(id);
+        self.try_detach(id).unwrap()
+    }
+
+    /// Unlink the entity from its parent & siblings, making it a root along with its members.
+    /// Returns [`TreeError::InvalidId`] if the entity isn't registered to the tree
+    pub fn try_detach(&mut self, id: SlotId) -> Result<(), TreeError> {
+        let index = id.index();
+        if index >= self.parent.len() { return Err(TreeError::InvalidId) }
+
+        let prev = self.prev_sibling[index].take();
+        let next = self.next_sibling[index].take();
+        let parent = self.parent[index].take();
 
         if let Some(prev) = prev {
             self.next_sibling[prev.index()] = next;
-        } else if let Some(parent) = self.get_parent(id) {
+        } else if let Some(parent) = parent {
             self.first_child[parent.index()] = next;
         }
 
         if let Some(next) = next {
             self.prev_sibling[next.index()] = prev;
         }
+
+        Ok(())
     }
 
     #[inline(always)]
     pub fn set_child(&mut self, id: SlotId, child: SlotId) {
-        self.detach(child);
-        self.insert_with_parent(child, id);
+        self.try_set_child(id, child).unwrap()
+    }
+
+    /// Move `child` to be the last child of `id`.
+    /// Returns [`TreeError::InvalidParent`] if `id` isn't registered, [`TreeError::InvalidId`] if `child` isn't,
+    /// or [`TreeError::Cycle`] if `child` is `id` itself or one of its ancestors
+    pub fn try_set_child(&mut self, id: SlotId, child: SlotId) -> Result<(), TreeError> {
+        if id.index() >= self.parent.len() { return Err(TreeError::InvalidParent) }
+        if child.index() >= self.parent.len() { return Err(TreeError::InvalidId) }
+
+        if child == id || self.iter_ancestry(id).any(|ancestor| ancestor == child) {
+            return Err(TreeError::Cycle)
+        }
+
+        self.try_detach(child)?;
+        self.try_insert_with_parent(child, id)
     }
 
     #[inline(always)]
@@ -278,17 +304,6 @@ impl SparseTree {
             .count()
     }
 
-    fn ancestors_with_sibling(&self, id: SlotId) -> Vec<bool> {
-        let mut current = id;
-        let mut loc = vec![];
-        while let Some(parent) = self.get_parent(current) {
-            loc.push(self.get_next_sibling(parent).is_some());
-            current = parent;
-        }
-        loc.reverse();
-        loc
-    }
-
     pub fn is_member_of(&self, id: SlotId, ancestor: SlotId) -> bool {
         if self.get_first_child(ancestor).is_none() {
             return false
@@ -303,14 +318,113 @@ impl SparseTree {
         check == ancestor
     }
 
+    /// The entity followed by its ancestors, the last one being its root.
+    /// If you want to avoid the allocation use [`iter_ancestry`](Self::iter_ancestry)
+    pub fn path_to_root(&self, id: SlotId) -> Vec<SlotId> {
+        std::iter::once(id)
+            .chain(self.iter_ancestry(id))
+            .collect()
+    }
+
+    /// Like [`path_to_root`](Self::path_to_root), but starting from the root
+    pub fn path_from_root(&self, id: SlotId) -> Vec<SlotId> {
+        let mut path = self.path_to_root(id);
+        path.reverse();
+        path
+    }
+
+    /// The deepest entity which both `a` and `b` are a member of, including themselves.
+    /// Returns `None` if they belong to different roots
+    pub fn common_ancestor(&self, a: SlotId, b: SlotId) -> Option<SlotId> {
+        let path = self.path_to_root(a);
+
+        std::iter::once(b)
+            .chain(self.iter_ancestry(b))
+            .find(|id| path.contains(id))
+    }
+
     pub fn len(&self, start: SlotId) -> usize {
         self.iter_depth(start).count()
     }
 
+    /// The number of members without children, `start` itself counts when it has none
+    pub fn leaf_count(&self, start: SlotId) -> usize {
+        self.iter_leaves(start).count()
+    }
+
+    /// The distance from `start` to its deepest member, 0 when it has no children
+    pub fn max_depth(&self, start: SlotId) -> usize {
+        let base = self.entity_depth(start);
+
+        self.iter_leaves(start)
+            .map(|leaf| self.entity_depth(leaf) - base)
+            .max()
+            .unwrap_or_default()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.parent.is_empty()
     }
 
+    /// Check the links between the entities are consistent: every parent lists exactly the children pointing to it,
+    /// the sibling links are symmetric, and following the parents always ends at a root
+    pub fn validate(&self) -> Result<(), TreeError> {
+        let len = self.parent.len();
+        let index_of = |id: SlotId| {
+            let index = id.index();
+            if index < len { Ok(index) } else { Err(TreeError::InvalidId) }
+        };
+
+        let mut pointing_back = vec![0; len];
+        let mut listed = vec![0; len];
+
+        for (index, listed) in listed.iter_mut().enumerate() {
+            let mut current = index;
+            let mut steps = 0;
+            while let Some(parent) = self.parent[current] {
+                current = index_of(parent)?;
+                steps += 1;
+                if steps > len { return Err(TreeError::Cycle) }
+            }
+
+            if let Some(parent) = self.parent[index] {
+                pointing_back[parent.index()] += 1;
+            }
+
+            let mut next = self.first_child[index];
+            let mut prev = None::<SlotId>;
+            while let Some(child) = next {
+                let child_index = index_of(child)?;
+
+                if self.parent[child_index].map(|parent| parent.index()) != Some(index) {
+                    return Err(TreeError::BrokenParentLink)
+                }
+
+                if self.prev_sibling[child_index].map(|id| id.index()) != prev.map(|id| id.index()) {
+                    return Err(TreeError::BrokenSiblingLink)
+                }
+
+                *listed += 1;
+                if *listed > len { return Err(TreeError::Cycle) }
+
+                prev = Some(child);
+                next = self.next_sibling[child_index];
+            }
+
+            if let Some(next) = self.next_sibling[index]
+                && self.prev_sibling[index_of(next)?].map(|id| id.index()) != Some(index)
+            {
+                return Err(TreeError::BrokenSiblingLink)
+            }
+        }
+
+        if pointing_back != listed {
+            return Err(TreeError::BrokenParentLink)
+        }
+
+        Ok(())
+    }
+
     pub fn contains(&self, id: SlotId) -> bool {
         id.index() <= self.parent.len()
         && (
@@ -332,6 +446,22 @@ impl SparseTree {
         self.prev_sibling.push(None);
     }
 
+    /// Every entity paired with its parent, parents always come before their children.
+    /// Feed them into [`from_edges`](Self::from_edges) to rebuild the same tree
+    pub fn to_edges(&self) -> Vec<(SlotId, Option<SlotId>)> {
+        self.roots()
+            .flat_map(|root| self.iter_depth(root))
+            .map(|id| (id, self.get_parent(id)))
+            .collect()
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (SlotId, Option<SlotId>)>) -> Self {
+        let edges = edges.into_iter();
+        let mut tree = Self::with_capacity(edges.size_hint().0);
+        edges.for_each(|(id, parent)| tree.insert(id, parent));
+        tree
+    }
+
     /// iterate the children of the entity
     pub fn iter_children<'a>(&'a self, id: SlotId) -> TreeChildIter<'a> {
         TreeChildIter::new(self, id)
@@ -342,6 +472,11 @@ impl SparseTree {
         TreeDepthIter::new(self, id)
     }
 
+    /// iterate the members of the entity which have no children, in depth first order
+    pub fn iter_leaves<'a>(&'a self, id: SlotId) -> impl Iterator<Item = SlotId> + 'a {
+        self.iter_depth(id).filter(|member| self.get_first_child(*member).is_none())
+    }
+
     /// iterate the entity's parent upward
     pub fn iter_ancestry<'a>(&'a self, id: SlotId) -> TreeAncestryIter<'a> {
         TreeAncestryIter::new(self, id)
@@ -361,47 +496,58 @@ impl SparseTree {
     }
 
     pub fn recursively_fill_string_buffer(&self, start: Option<SlotId>, s: &mut String) {
-        match start {
-            Some(parent) => {
-                self.iter_children(parent).for_each(|child| {
-                    let ancestor_sibling = self.ancestors_with_sibling(child);
-                    let loc = ancestor_sibling
-                        .iter()
-                        .enumerate()
-                        .map(|(i, val)| val.then_some(i).unwrap_or_default())
-                        .max()
-                        .unwrap_or_default();
-
-                    let depth = self.entity_depth(child);
-                    let frame = self.get_frame(child);
-                    let len = frame.len() / 2;
-
-                    let mut connector_indent = 0;
-                    for yes in ancestor_sibling {
-                        let mut reducer = 0;
-                        if yes {
-                            s.push_str(format!("{:connector_indent$}│", "").as_str());
-                            connector_indent = 0;
-                            reducer = 1;
-                        }
-                        connector_indent += len - reducer;
-                    }
+        // writing into a String never fails
+        let _ = match start {
+            Some(parent) => self.write_children(parent, s),
+            None => self.write_tree(s),
+        };
+    }
 
-                    let modifier = if loc > 0 { 1 } else { 0 };
-                    let indent = len * (depth - loc) - modifier;
-                    let format = format!("{:indent$}{frame} {child:?}\n", "");
-                    s.push_str(format.as_str());
-
-                    self.recursively_fill_string_buffer(Some(child), s);
-                });
-            },
-            None => {
-                self.roots().for_each(|root| {
-                    s.push_str(format!(" > {root:?}\n").as_str());
-                    self.recursively_fill_string_buffer(Some(root), s);
-                });
-            },
-        }
+    /// Draw the tree into `w`, streaming each line without intermediate allocations
+    pub fn write_tree<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        self.roots().try_for_each(|root| {
+            writeln!(w, " > {root:?}")?;
+            self.write_children(root, w)
+        })
+    }
+
+    fn write_children<W: std::fmt::Write>(&self, parent: SlotId, w: &mut W) -> std::fmt::Result {
+        self.iter_children(parent).try_for_each(|child| {
+            // root first, whether each ancestor of the child has a next sibling
+            let mut ancestor_sibling = self.iter_ancestry(child)
+                .map(|ancestor| self.get_next_sibling(ancestor).is_some())
+                .collect::<Vec<_>>();
+            ancestor_sibling.reverse();
+
+            let depth = ancestor_sibling.len();
+
+            let loc = ancestor_sibling
+                .iter()
+                .enumerate()
+                .filter_map(|(i, val)| val.then_some(i))
+                .max()
+                .unwrap_or_default();
+
+            let frame = self.get_frame(child);
+            let len = frame.len() / 2;
+
+            let mut connector_indent = 0;
+            for &yes in &ancestor_sibling {
+                let mut reducer = 0;
+                if yes {
+                    write!(w, "{:connector_indent$}│", "")?;
+                    connector_indent = 0;
+                    reducer = 1;
+                }
+                connector_indent += len - reducer;
+            }
+
+            let modifier = if loc > 0 { 1 } else { 0 };
+            let indent = len * (depth - loc) - modifier;
+            writeln!(w, "{:indent$}{frame} {child:?}", "")?;
+
+            self.write_children(child, w)
+        })
     }
 }
 
@@ -413,12 +559,9 @@ impl SparseTree {
 #########################################################
 */
 
-// FIXME: there are two spot which created unnecessary allocation on get_all_children + get_all_roots
 impl std::fmt::Debug for SparseTree {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        self.recursively_fill_string_buffer(None, &mut s);
-        write!(f, "{s}")
+        self.write_tree(f)
     }
 }
 
@@ -426,6 +569,12 @@ impl std::fmt::Debug for SparseTree {
 pub enum TreeError {
     InvalidParent,
     InvalidId,
+    /// A child's parent doesn't list it among its children, or the other way around
+    BrokenParentLink,
+    /// The next & previous sibling links don't point at each other
+    BrokenSiblingLink,
+    /// An entity is its own ancestor or sibling
+    Cycle,
 }
 
 impl std::fmt::Display for TreeError {
@@ -557,6 +706,8 @@ impl<'a> Iterator for TreeDepthIter<'a> {
         if let Some(current) = next {
             if let Some(first_child) = self.tree.get_first_child(current) {
                 self.next = Some(first_child);
+            } else if current == self.id {
+                // a childless start has no members besides itself
             } else if let Some(next_sibling) = self.tree.get_next_sibling(current) {
                 self.next = Some(next_sibling);
             } else {
@@ -684,6 +835,249 @@ mod tree_test {
         (manager, tree)
     }
 
+    #[test]
+    fn common_ancestor() {
+        let mut manager = SlotMap::new();
+        let ids = (0..8).map(|_| manager.insert(())).collect::<Vec<_>>();
+        let mut tree = SparseTree::default();
+
+        //      0          7
+        //    /   \
+        //   1     2
+        //  / \     \
+        // 3   4     5
+        //     |
+        //     6
+        tree.insert(ids[0], None);
+        tree.insert(ids[1], Some(ids[0]));
+        tree.insert(ids[2], Some(ids[0]));
+        tree.insert(ids[3], Some(ids[1]));
+        tree.insert(ids[4], Some(ids[1]));
+        tree.insert(ids[5], Some(ids[2]));
+        tree.insert(ids[6], Some(ids[4]));
+        tree.insert(ids[7], None);
+
+        // siblings
+        assert_eq!(tree.common_ancestor(ids[3], ids[4]), Some(ids[1]));
+        assert_eq!(tree.common_ancestor(ids[1], ids[2]), Some(ids[0]));
+        assert_eq!(tree.common_ancestor(ids[6], ids[5]), Some(ids[0]));
+        assert_eq!(tree.common_ancestor(ids[6], ids[3]), Some(ids[1]));
+
+        // ancestor and descendant
+        assert_eq!(tree.common_ancestor(ids[1], ids[6]), Some(ids[1]));
+        assert_eq!(tree.common_ancestor(ids[6], ids[0]), Some(ids[0]));
+        assert_eq!(tree.common_ancestor(ids[4], ids[4]), Some(ids[4]));
+
+        // different roots
+        assert_eq!(tree.common_ancestor(ids[6], ids[7]), None);
+    }
+
+    #[test]
+    fn leaf_count_and_max_depth() {
+        let mut manager = SlotMap::new();
+        let ids = (0..8).map(|_| manager.insert(())).collect::<Vec<_>>();
+        let mut tree = SparseTree::default();
+
+        //      0          7
+        //    /   \
+        //   1     2
+        //  / \     \
+        // 3   4     5
+        //     |
+        //     6
+        tree.insert(ids[0], None);
+        tree.insert(ids[1], Some(ids[0]));
+        tree.insert(ids[2], Some(ids[0]));
+        tree.insert(ids[3], Some(ids[1]));
+        tree.insert(ids[4], Some(ids[1]));
+        tree.insert(ids[5], Some(ids[2]));
+        tree.insert(ids[6], Some(ids[4]));
+        tree.insert(ids[7], None);
+
+        assert_eq!(tree.leaf_count(ids[0]), 3);
+        assert_eq!(tree.max_depth(ids[0]), 3);
+
+        assert_eq!(tree.leaf_count(ids[1]), 2);
+        assert_eq!(tree.max_depth(ids[1]), 2);
+
+        assert_eq!(tree.leaf_count(ids[2]), 1);
+        assert_eq!(tree.max_depth(ids[2]), 1);
+
+        assert_eq!(tree.leaf_count(ids[7]), 1);
+        assert_eq!(tree.max_depth(ids[7]), 0);
+    }
+
+    #[test]
+    fn validate() {
+        let mut manager = SlotMap::new();
+        let ids = (0..5).map(|_| manager.insert(())).collect::<Vec<_>>();
+        let mut tree = SparseTree::default();
+
+        //      0
+        //    / | \
+        //   1  2  3
+        //   |
+        //   4
+        tree.insert(ids[0], None);
+        tree.insert(ids[1], Some(ids[0]));
+        tree.insert(ids[2], Some(ids[0]));
+        tree.insert(ids[3], Some(ids[0]));
+        tree.insert(ids[4], Some(ids[1]));
+        assert!(tree.validate().is_ok());
+        assert!(setup_tree(11).1.validate().is_ok());
+
+        let index = |i: usize| ids[i].index();
+
+        // 4 claims 2 as its parent, but 1 still lists it
+        let mut corrupted = SparseTree::from_edges(tree.to_edges());
+        corrupted.parent[index(4)] = Some(ids[2]);
+        assert!(matches!(corrupted.validate(), Err(TreeError::BrokenParentLink)));
+
+        // 2 skips 3 going forward, but 3 still points back to 2
+        let mut corrupted = SparseTree::from_edges(tree.to_edges());
+        corrupted.next_sibling[index(1)] = Some(ids[3]);
+        assert!(matches!(corrupted.validate(), Err(TreeError::BrokenSiblingLink)));
+
+        // 0 becomes a child of its own grandchild
+        let mut corrupted = SparseTree::from_edges(tree.to_edges());
+        corrupted.parent[index(0)] = Some(ids[4]);
+        assert!(matches!(corrupted.validate(), Err(TreeError::Cycle)));
+    }
+
+    #[test]
+    fn fallible_mutations() {
+        let mut manager = SlotMap::new();
+        let ids = (0..4).map(|_| manager.insert(())).collect::<Vec<_>>();
+        let mut tree = SparseTree::default();
+
+        tree.insert(ids[0], None);
+        tree.insert(ids[1], Some(ids[0]));
+        tree.insert(ids[2], Some(ids[0]));
+        tree.insert(ids[3], Some(ids[1]));
+
+        let unknown = SlotId::new(100, 0);
+        assert!(matches!(tree.try_detach(unknown), Err(TreeError::InvalidId)));
+        assert!(matches!(tree.try_set_child(unknown, ids[1]), Err(TreeError::InvalidParent)));
+        assert!(matches!(tree.try_set_child(ids[0], unknown), Err(TreeError::InvalidId)));
+        assert!(matches!(tree.try_add_sibling(unknown, ids[1]), Err(TreeError::InvalidId)));
+        assert!(matches!(tree.try_set_child(ids[3], ids[0]), Err(TreeError::Cycle)));
+        assert!(matches!(tree.try_set_child(ids[1], ids[1]), Err(TreeError::Cycle)));
+        assert!(tree.validate().is_ok());
+
+        // 1 moves under 2, and keeps 3
+        assert!(tree.try_set_child(ids[2], ids[1]).is_ok());
+        assert!(tree.validate().is_ok());
+        assert_eq!(tree.get_parent(ids[1]), Some(ids[2]));
+        assert_eq!(tree.get_parent(ids[3]), Some(ids[1]));
+        assert_eq!(tree.get_all_children(ids[0]), [ids[2]]);
+
+        assert!(tree.try_detach(ids[1]).is_ok());
+        assert!(tree.validate().is_ok());
+        assert_eq!(tree.get_parent(ids[1]), None);
+        assert_eq!(tree.child_count(ids[2]), 0);
+    }
+
+    #[test]
+    fn edges_round_trip() {
+        let (mut manager, mut tree) = setup_tree(11);
+        let extra_root = manager.insert(());
+        tree.insert(extra_root, None);
+        tree.insert(manager.insert(()), Some(extra_root));
+
+        let edges = tree.to_edges();
+        let rebuilt = SparseTree::from_edges(edges.iter().copied());
+
+        assert_eq!(rebuilt.to_edges(), edges);
+        assert_eq!(rebuilt.parent, tree.parent);
+        assert_eq!(rebuilt.first_child, tree.first_child);
+        assert_eq!(rebuilt.next_sibling, tree.next_sibling);
+        assert_eq!(rebuilt.prev_sibling, tree.prev_sibling);
+
+        let order = |tree: &SparseTree| tree.roots()
+            .flat_map(|root| tree.iter_depth(root))
+            .collect::<Vec<_>>();
+        assert_eq!(order(&rebuilt), order(&tree));
+    }
+
+    #[test]
+    fn path_to_root() {
+        let (_, tree) = setup_tree(11);
+        let deep = SlotId::new(6, 0);
+
+        let path = tree.path_to_root(deep);
+        let expected = [6, 5, 2, 1, 0].map(|i| SlotId::new(i, 0));
+        assert_eq!(path, expected);
+        assert_eq!(path.last().copied(), tree.get_root(deep));
+
+        let reversed = tree.path_from_root(deep);
+        assert_eq!(reversed.first().copied(), tree.get_root(deep));
+        assert!(reversed.iter().eq(expected.iter().rev()));
+
+        // a root is its own path
+        assert_eq!(tree.path_to_root(SlotId::new(0, 0)), [SlotId::new(0, 0)]);
+    }
+
+    #[test]
+    fn write_tree() {
+        let mut manager = SlotMap::new();
+        let ids = (0..8).map(|_| manager.insert(())).collect::<Vec<_>>();
+        let mut tree = SparseTree::default();
+
+        tree.insert(ids[0], None);
+        tree.insert(ids[1], Some(ids[0]));
+        tree.insert(ids[2], Some(ids[0]));
+        tree.insert(ids[3], Some(ids[1]));
+        tree.insert(ids[4], Some(ids[1]));
+        tree.insert(ids[5], Some(ids[2]));
+        tree.insert(ids[6], Some(ids[4]));
+        tree.insert(ids[7], None);
+
+        let expected = concat!(
+            " > SlotId(0)\n",
+            "   ├─ SlotId(1)\n",
+            "   │  ├─ SlotId(3)\n",
+            "   │  └─ SlotId(4)\n",
+            "   │     └─ SlotId(6)\n",
+            "   └─ SlotId(2)\n",
+            "      └─ SlotId(5)\n",
+            " > SlotId(7)\n",
+        );
+
+        let mut s = String::new();
+        tree.write_tree(&mut s).unwrap();
+        assert_eq!(s, expected);
+        assert_eq!(format!("{tree:?}"), expected);
+    }
+
+    #[test]
+    fn leaves() {
+        let mut manager = SlotMap::new();
+        let ids = (0..7).map(|_| manager.insert(())).collect::<Vec<_>>();
+        let mut tree = SparseTree::default();
+
+        //      0
+        //    / | \
+        //   1  2  3
+        //  / \    \
+        // 4   5    6
+        tree.insert(ids[0], None);
+        tree.insert(ids[1], Some(ids[0]));
+        tree.insert(ids[2], Some(ids[0]));
+        tree.insert(ids[3], Some(ids[0]));
+        tree.insert(ids[4], Some(ids[1]));
+        tree.insert(ids[5], Some(ids[1]));
+        tree.insert(ids[6], Some(ids[3]));
+
+        let leaves = tree.iter_leaves(ids[0]).collect::<Vec<_>>();
+        assert_eq!(leaves, [ids[4], ids[5], ids[2], ids[6]]);
+
+        let leaves = tree.iter_leaves(ids[1]).collect::<Vec<_>>();
+        assert_eq!(leaves, [ids[4], ids[5]]);
+
+        // a leaf is its own only leaf
+        assert_eq!(tree.iter_leaves(ids[2]).collect::<Vec<_>>(), [ids[2]]);
+    }
+
     #[test]
     fn tree_test() {
         let (_, tree) = setup_tree(11);
@@ -0,0 +1,25 @@
+/// Write the values as little-endian f32, `B` has to be 4 bytes per value
+pub(crate) fn encode<const N: usize, const B: usize>(values: [f32; N]) -> [u8; B] {
+    const { assert!(N * 4 == B) }
+
+    let mut bytes = [0; B];
+    bytes
+        .chunks_exact_mut(4)
+        .zip(values)
+        .for_each(|(chunk, value)| chunk.copy_from_slice(&value.to_le_bytes()));
+
+    bytes
+}
+
+/// Read back what [`encode`] wrote, [`None`] if the length doesn't match
+pub(crate) fn decode<const N: usize>(bytes: &[u8]) -> Option<[f32; N]> {
+    if bytes.len() != N * 4 { return None }
+
+    let mut values = [0.; N];
+    values
+        .iter_mut()
+        .zip(bytes.chunks_exact(4))
+        .for_each(|(value, chunk)| *value = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+
+    Some(values)
+}
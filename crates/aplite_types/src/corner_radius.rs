@@ -1,3 +1,5 @@
+use crate::Size;
+
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct CornerRadius {
@@ -43,6 +45,37 @@ impl CornerRadius {
         self.tr = val;
     }
 
+    /// The radius in pixels drawn for the corner value `r` on a rect `width` wide, 100 is a quarter of the width
+    pub fn to_px(r: u8, width: f32) -> f32 {
+        r as f32 * width / 400.
+    }
+
+    /// Limit each corner so the radius doesn't exceed half of the shorter side of `size`.
+    /// The values are relative to the width, see [`to_px`](Self::to_px), so for a 100x40 rect the limit is 80
+    pub fn clamped(&self, size: Size) -> Self {
+        let max = if size.width > 0. {
+            (200. * size.width.min(size.height) / size.width).floor().min(u8::MAX as f32) as u8
+        } else {
+            0
+        };
+
+        Self {
+            tl: self.tl.min(max),
+            bl: self.bl.min(max),
+            br: self.br.min(max),
+            tr: self.tr.min(max),
+        }
+    }
+
+    pub const fn unpack(val: u32) -> Self {
+        Self {
+            tl: (val >> 24) as u8,
+            bl: ((val >> 16) & 0xFF) as u8,
+            br: ((val >> 8) & 0xFF) as u8,
+            tr: (val & 0xFF) as u8,
+        }
+    }
+
     pub fn pack_u32(&self) -> u32 {
         (self.tl as u32) << 24
         | (self.bl as u32) << 16
@@ -62,3 +95,24 @@ impl From<u8> for CornerRadius {
         Self::splat(value)
     }
 }
+
+#[cfg(test)]
+mod corner_radius_test {
+    use super::*;
+
+    #[test]
+    fn clamp_to_shorter_side() {
+        // 255 is a radius of 63.75 on a 100x40 rect, limited to 80 which is 20
+        let clamped = CornerRadius::splat(255).clamped(Size::new(100., 40.));
+        assert_eq!(clamped, CornerRadius::splat(80));
+        assert_eq!(CornerRadius::to_px(clamped.tl, 100.), 20.);
+
+        // 60 is 15, which already fits
+        let small = CornerRadius::new(10, 60, 20, 90).clamped(Size::new(100., 40.));
+        assert_eq!(small, CornerRadius::new(10, 60, 20, 80));
+
+        // tall rects are limited by the width itself
+        let tall = CornerRadius::splat(255).clamped(Size::new(40., 100.));
+        assert_eq!(tall, CornerRadius::splat(200));
+    }
+}
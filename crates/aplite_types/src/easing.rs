@@ -0,0 +1,132 @@
+/// Every easing maps a linear progress `t` in `0.0..=1.0` into an eased one, `f(0.0) == 0.0` and `f(1.0) == 1.0`
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+pub fn ease_in_quad(t: f32) -> f32 {
+    t * t
+}
+
+pub fn ease_out_quad(t: f32) -> f32 {
+    1. - (1. - t) * (1. - t)
+}
+
+pub fn ease_in_out_quad(t: f32) -> f32 {
+    if t < 0.5 {
+        2. * t * t
+    } else {
+        1. - (-2. * t + 2.).powi(2) / 2.
+    }
+}
+
+pub fn ease_in_out_cubic(t: f32) -> f32 {
+    if t < 0.5 {
+        4. * t * t * t
+    } else {
+        1. - (-2. * t + 2.).powi(3) / 2.
+    }
+}
+
+/// CSS-like cubic bezier curve from `(0, 0)` to `(1, 1)` with control points `(x1, y1)` and `(x2, y2)`.
+/// `x1` and `x2` are clamped to `0.0..=1.0` so the curve stays a function of `t`
+pub fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32) -> impl Fn(f32) -> f32 + Copy + 'static {
+    let x1 = x1.clamp(0., 1.);
+    let x2 = x2.clamp(0., 1.);
+
+    move |t| {
+        if t <= 0. { return 0. }
+        if t >= 1. { return 1. }
+
+        let s = solve_curve_x(x1, x2, t);
+        bezier(y1, y2, s)
+    }
+}
+
+/// One dimension of the curve, with the end points fixed at 0 and 1
+#[inline(always)]
+fn bezier(p1: f32, p2: f32, s: f32) -> f32 {
+    let inv = 1. - s;
+    3. * inv * inv * s * p1 + 3. * inv * s * s * p2 + s * s * s
+}
+
+#[inline(always)]
+fn bezier_derivative(p1: f32, p2: f32, s: f32) -> f32 {
+    let inv = 1. - s;
+    3. * inv * inv * p1 + 6. * inv * s * (p2 - p1) + 3. * s * s * (1. - p2)
+}
+
+/// Find the curve parameter whose x equals `x`: newton first, bisection as the fallback
+fn solve_curve_x(x1: f32, x2: f32, x: f32) -> f32 {
+    const EPSILON: f32 = 1e-6;
+
+    let mut s = x;
+    for _ in 0..8 {
+        let err = bezier(x1, x2, s) - x;
+        if err.abs() < EPSILON { return s }
+
+        let d = bezier_derivative(x1, x2, s);
+        if d.abs() < EPSILON { break }
+
+        s -= err / d;
+    }
+
+    let (mut lo, mut hi) = (0f32, 1f32);
+    s = x;
+    while hi - lo > EPSILON {
+        let val = bezier(x1, x2, s);
+        if (val - x).abs() < EPSILON { break }
+
+        if val < x {
+            lo = s;
+        } else {
+            hi = s;
+        }
+
+        s = lo.midpoint(hi);
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod easing_test {
+    use super::*;
+
+    fn check(name: &str, f: impl Fn(f32) -> f32) {
+        assert!(f(0.).abs() < 1e-4, "{name}: f(0) = {}", f(0.));
+        assert!((f(1.) - 1.).abs() < 1e-4, "{name}: f(1) = {}", f(1.));
+
+        let mut prev = f(0.);
+        for i in 1..=100 {
+            let val = f(i as f32 / 100.);
+            assert!(val >= prev - 1e-5, "{name} is not monotonic at {i}");
+            prev = val;
+        }
+    }
+
+    #[test]
+    fn boundaries_and_monotonic() {
+        check("linear", linear);
+        check("ease_in_quad", ease_in_quad);
+        check("ease_out_quad", ease_out_quad);
+        check("ease_in_out_quad", ease_in_out_quad);
+        check("ease_in_out_cubic", ease_in_out_cubic);
+        check("ease", cubic_bezier(0.25, 0.1, 0.25, 1.));
+        check("ease_in_out", cubic_bezier(0.42, 0., 0.58, 1.));
+    }
+
+    #[test]
+    fn bezier_matches_known_curves() {
+        let linear_bezier = cubic_bezier(0., 0., 1., 1.);
+        let quad_like = cubic_bezier(0.42, 0., 0.58, 1.);
+
+        for i in 0..=10 {
+            let t = i as f32 / 10.;
+            assert!((linear_bezier(t) - t).abs() < 1e-4);
+        }
+
+        // symmetric curve
+        assert!((quad_like(0.5) - 0.5).abs() < 1e-4);
+        assert!((quad_like(0.25) + quad_like(0.75) - 1.).abs() < 1e-4);
+    }
+}
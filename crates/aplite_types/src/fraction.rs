@@ -1,6 +1,7 @@
 use crate::Size;
+use crate::size::gcd;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Fraction {
     numerator: f32,
     denominator: f32,
@@ -19,6 +20,16 @@ impl Fraction {
     pub const fn new(numerator: f32, denominator: f32) -> Self {
         Self { numerator, denominator }
     }
+
+    /// Divide both sides by their [`gcd`], keeping the denominator positive
+    /// # Panic
+    /// If the denominator is zero
+    fn reduced(numerator: f32, denominator: f32) -> Self {
+        assert!(denominator != 0.0, "fraction with a zero denominator");
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self::new(numerator / divisor, denominator / divisor)
+    }
 }
 
 impl From<(f32, f32)> for Fraction {
@@ -82,3 +93,60 @@ impl std::ops::Div<Fraction> for f32 {
         self * rhs.denominator / rhs.numerator
     }
 }
+
+impl std::ops::Add for Fraction {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::reduced(
+            self.numerator * rhs.denominator + rhs.numerator * self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl std::ops::Sub for Fraction {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::reduced(
+            self.numerator * rhs.denominator - rhs.numerator * self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl std::ops::Mul for Fraction {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::reduced(self.numerator * rhs.numerator, self.denominator * rhs.denominator)
+    }
+}
+
+impl std::ops::Div for Fraction {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::reduced(self.numerator * rhs.denominator, self.denominator * rhs.numerator)
+    }
+}
+
+#[cfg(test)]
+mod fraction_test {
+    use super::*;
+
+    #[test]
+    fn arithmetic_is_reduced() {
+        let half = Fraction::new(1., 2.);
+        let third = Fraction::new(1., 3.);
+
+        assert_eq!(half + third, Fraction::new(5., 6.));
+        assert_eq!(half - third, Fraction::new(1., 6.));
+        assert_eq!(third - half, Fraction::new(-1., 6.));
+        assert_eq!(Fraction::new(2., 4.) * Fraction::new(3., 9.), Fraction::new(1., 6.));
+        assert_eq!(half / Fraction::new(-3., 4.), Fraction::new(-2., 3.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_denominator() {
+        let _ = Fraction::new(1., 2.) / Fraction::new(0., 1.);
+    }
+}
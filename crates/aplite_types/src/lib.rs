@@ -7,6 +7,9 @@ mod shapes;
 mod paint;
 mod point;
 mod length;
+mod bytes;
+
+pub mod easing;
 
 pub use corner_radius::CornerRadius;
 pub use size::{gcd, Size};
@@ -31,6 +31,7 @@ impl Color {
         Self { r, g, b, a }
     }
 
+    /// The inverse of [`pack_u32`](Self::pack_u32)
     pub const fn unpack(val: u32) -> Self {
         let r = (val >> 24) as u8;
         let g = ((val >> 16) & 0xFF) as u8;
@@ -54,6 +55,7 @@ impl Color {
         }
     }
 
+    /// `0xRRGGBBAA`, red in the most significant byte & alpha in the least, the order the shader unpacks
     pub const fn pack_u32(self) -> u32 {
         ((self.r as u32) << 24)
         | ((self.g as u32) << 16)
@@ -116,3 +118,26 @@ pub mod gruvbox_dark {
     pub const ORANGE_0: Color = rgb(0xd65d0e);
     pub const ORANGE_1: Color = rgb(0xfe8019);
 }
+
+#[cfg(test)]
+mod color_test {
+    use super::*;
+
+    #[test]
+    fn pack_round_trip() {
+        let colors = [
+            basic::TRANSPARENT,
+            basic::WHITE,
+            basic::BLACK,
+            Color::new(0x12, 0x34, 0x56, 0x78),
+            Color::new(255, 0, 128, 1),
+        ];
+
+        for color in colors {
+            assert_eq!(Color::unpack(color.pack_u32()), color);
+        }
+
+        assert_eq!(Color::new(0x12, 0x34, 0x56, 0x78).pack_u32(), 0x12345678);
+        assert_eq!(Color::unpack(0x12345678), rgba(0x12345678));
+    }
+}
@@ -81,6 +81,21 @@ impl ImageRef {
                 }
             })
     }
+
+    /// Check if the source [`ImageData`] is still alive, meaning [`upgrade`](Self::upgrade) will return [`Some`]
+    pub fn is_alive(&self) -> bool {
+        self.bytes.strong_count() > 0
+    }
+
+    /// The number of [`ImageData`] currently holding the bytes
+    pub fn strong_count(&self) -> usize {
+        self.bytes.strong_count()
+    }
+
+    /// The number of [`ImageRef`] currently pointing to the bytes
+    pub fn weak_count(&self) -> usize {
+        self.bytes.weak_count()
+    }
 }
 
 impl Clone for ImageRef {
@@ -34,6 +34,16 @@ impl Point {
         [self.x, self.y]
     }
 
+    /// x then y, as little-endian f32
+    pub fn to_bytes(&self) -> [u8; 8] {
+        crate::bytes::encode([self.x, self.y])
+    }
+
+    /// The inverse of [`to_bytes`](Self::to_bytes), [`None`] if `bytes` isn't exactly 8 bytes long
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        crate::bytes::decode(bytes).map(Self::from_array)
+    }
+
     pub fn vec2f(self) -> Vec2f {
         Vec2f {
             x: self.x,
@@ -41,6 +51,11 @@ impl Point {
         }
     }
 
+    /// The position as a vector from the origin
+    pub const fn to_vec2f(self) -> Vec2f {
+        Vec2f::new(self.x, self.y)
+    }
+
     pub const fn min(self, other: Self) -> Self {
         Self::new(
             self.x.min(other.x),
@@ -89,3 +104,15 @@ impl PartialEq for Point {
         self.x == other.x && self.y == other.y
     }
 }
+
+#[cfg(test)]
+mod point_test {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        let point = Point::new(-1.5, 1e6);
+        assert_eq!(Point::from_bytes(&point.to_bytes()), Some(point));
+        assert_eq!(Point::from_bytes(&point.to_bytes()[..7]), None);
+    }
+}
@@ -33,6 +33,10 @@ impl Circle {
     pub const fn set_radius(&mut self, r: f32) {
         self.radius = r;
     }
+
+    pub fn contains_point(&self, p: Vec2f) -> bool {
+        (p.x - self.center_x).powi(2) + (p.y - self.center_y).powi(2) <= self.radius.powi(2)
+    }
 }
 
 impl PartialOrd for Circle {
@@ -104,6 +104,24 @@ impl Rect {
             && (self.y..self.max_y()).contains(&p.y)
     }
 
+    /// The smallest [`Rect`] containing both rects
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_vec2f(
+            self.vec2f().min(other.vec2f()),
+            Vec2f::new(self.max_x().max(other.max_x()), self.max_y().max(other.max_y())),
+        )
+    }
+
+    /// x, y, width then height, as little-endian f32
+    pub fn to_bytes(&self) -> [u8; 16] {
+        crate::bytes::encode([self.x, self.y, self.width, self.height])
+    }
+
+    /// The inverse of [`to_bytes`](Self::to_bytes), [`None`] if `bytes` isn't exactly 16 bytes long
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        crate::bytes::decode(bytes).map(Self::from_array)
+    }
+
     pub fn adjust_width(&mut self, aspect_ratio: Fraction) {
         self.width = self.height * aspect_ratio
     }
@@ -137,6 +155,21 @@ impl From<(Vec2f, Size)> for Rect {
     }
 }
 
+/// Scales the position & the size, e.g. converting between logical & physical pixels
+impl std::ops::Mul<f32> for Rect {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.width * rhs, self.height * rhs)
+    }
+}
+
+impl std::ops::Div<f32> for Rect {
+    type Output = Self;
+    fn div(self, rhs: f32) -> Self::Output {
+        Self::new(self.x / rhs, self.y / rhs, self.width / rhs, self.height / rhs)
+    }
+}
+
 impl PartialEq for Rect {
     fn eq(&self, other: &Self) -> bool {
         self.vec2f().eq(&other.vec2f())
@@ -157,3 +190,24 @@ impl Ord for Rect {
         self.size().cmp(&other.size())
     }
 }
+
+#[cfg(test)]
+mod rect_test {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        let rect = Rect::new(10., -20., 30.25, 40.);
+        let bytes = rect.to_bytes();
+        assert_eq!(&bytes[..4], 10f32.to_le_bytes());
+        assert_eq!(Rect::from_bytes(&bytes), Some(rect));
+        assert_eq!(Rect::from_bytes(&bytes[..8]), None);
+    }
+
+    #[test]
+    fn scale() {
+        let rect = Rect::new(10., 20., 30., 40.);
+        assert_eq!(rect * 2., Rect::new(20., 40., 60., 80.));
+        assert_eq!(rect / 10., Rect::new(1., 2., 3., 4.));
+    }
+}
@@ -84,4 +84,53 @@ impl RoundedRect {
     pub const fn set_radius_each(&mut self, tl: u8, bl: u8, br: u8, tr: u8) {
         self.radius.set_each(tl, bl, br, tr)
     }
+
+    /// Like [`Rect::contains`], but the points cut off by the rounded corners are outside.
+    /// The corners are converted with [`CornerRadius::to_px`], so this matches the drawn curve
+    pub fn contains_point(&self, p: Vec2f) -> bool {
+        let rect = Rect::new(self.x, self.y, self.width, self.height);
+        if !rect.contains(&p) {
+            return false;
+        }
+
+        let radius = self.radius.clamped(self.size());
+        let to_px = |r: u8| CornerRadius::to_px(r, self.width);
+
+        let left = p.x < self.x + self.width / 2.;
+        let top = p.y < self.y + self.height / 2.;
+
+        let r = match (left, top) {
+            (true, true) => to_px(radius.tl),
+            (true, false) => to_px(radius.bl),
+            (false, false) => to_px(radius.br),
+            (false, true) => to_px(radius.tr),
+        };
+
+        // the center of the corner's arc
+        let cx = if left { self.x + r } else { self.x + self.width - r };
+        let cy = if top { self.y + r } else { self.y + self.height - r };
+
+        let outside_x = if left { p.x < cx } else { p.x > cx };
+        let outside_y = if top { p.y < cy } else { p.y > cy };
+
+        !(outside_x && outside_y) || (p.x - cx).powi(2) + (p.y - cy).powi(2) <= r * r
+    }
+}
+
+#[cfg(test)]
+mod rounded_rect_test {
+    use super::*;
+
+    #[test]
+    fn corners_are_cut_off() {
+        // radius of 20 on every corner
+        let rounded = RoundedRect::from_rect_radius_splat(Rect::new(0., 0., 100., 100.), 80);
+
+        assert!(rounded.contains_point(Vec2f::new(50., 50.)));
+        assert!(rounded.contains_point(Vec2f::new(0., 50.)));
+        assert!(!rounded.contains_point(Vec2f::new(1., 1.)));
+        assert!(!rounded.contains_point(Vec2f::new(99., 99.)));
+        assert!(rounded.contains_point(Vec2f::new(8., 8.)));
+        assert!(!rounded.contains_point(Vec2f::new(101., 50.)));
+    }
 }
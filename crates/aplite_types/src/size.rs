@@ -51,12 +51,39 @@ impl Size {
         Fraction::new(self.width / gcd, self.height / gcd)
     }
 
+    /// Scale while keeping the aspect ratio, to the largest size which fits inside `bounds`
+    pub fn fit_within(&self, bounds: Size) -> Size {
+        *self * (bounds.width / self.width).min(bounds.height / self.height)
+    }
+
+    /// Scale while keeping the aspect ratio, to the smallest size which covers the whole `bounds`
+    pub fn cover(&self, bounds: Size) -> Size {
+        *self * (bounds.width / self.width).max(bounds.height / self.height)
+    }
+
+    /// width then height, as little-endian f32
+    pub fn to_bytes(&self) -> [u8; 8] {
+        crate::bytes::encode([self.width, self.height])
+    }
+
+    /// The inverse of [`to_bytes`](Self::to_bytes), [`None`] if `bytes` isn't exactly 8 bytes long
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        crate::bytes::decode(bytes).map(|[width, height]| Self::new(width, height))
+    }
+
+    /// width as x & height as y
+    pub const fn to_vec2f(self) -> crate::Vec2f {
+        crate::Vec2f::new(self.width, self.height)
+    }
+
     pub fn rect(self) -> crate::Rect {
         crate::Rect::from_size(self)
     }
 }
 
 // arithmetic operation
+// with a scalar both sides are scaled, with another size it's component-wise:
+// `width * width` and `height * height`, or `width / width` and `height / height`
 
 impl std::ops::Mul<f32> for Size {
     type Output = Self;
@@ -78,6 +105,19 @@ impl std::ops::Div<f32> for Size {
     }
 }
 
+impl std::ops::DivAssign<f32> for Size {
+    fn div_assign(&mut self, rhs: f32) {
+        *self = *self / rhs
+    }
+}
+
+impl std::ops::Mul<Self> for Size {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(self.width * rhs.width, self.height * rhs.height)
+    }
+}
+
 impl std::ops::Div<Self> for Size {
     type Output = Self;
     fn div(self, rhs: Self) -> Self::Output {
@@ -150,6 +190,12 @@ impl From<(u32, u32)> for Size {
     }
 }
 
+impl From<crate::Vec2f> for Size {
+    fn from(value: crate::Vec2f) -> Self {
+        value.to_size()
+    }
+}
+
 impl From<(f32, f32)> for Size {
     fn from(value: (f32, f32)) -> Self {
         Self::new(value.0, value.1)
@@ -158,13 +204,13 @@ impl From<(f32, f32)> for Size {
 
 /// global common divisor
 pub fn gcd(a: f32, b: f32) -> f32 {
-    let mut ret = a;
-    let mut rem = b;
+    let mut ret = a.abs();
+    let mut rem = b.abs();
     loop {
         if rem == 0.0 { break }
         let temp = ret;
         ret = rem;
-        rem = temp / rem;
+        rem = temp % rem;
     }
     ret
 }
@@ -179,6 +225,46 @@ mod gcd_test {
         let height = 1600.;
         let gcd = gcd(width, height);
         let fraction = [width/gcd, height/gcd];
-        assert_eq!(fraction, [5., 3.]);
+        assert_eq!(fraction, [8., 5.]);
+    }
+}
+
+#[cfg(test)]
+mod size_test {
+    use super::*;
+
+    #[test]
+    fn aspect_ratio_and_scaling() {
+        let size = Size::new(1920., 1080.);
+        assert_eq!(size.aspect_ratio(), Fraction::new(16., 9.));
+
+        assert_eq!(size.fit_within(Size::square(480.)), Size::new(480., 270.));
+        assert_eq!(size.cover(Size::square(480.)), Size::new(853.3333, 480.));
+        assert_eq!(Size::new(100., 200.).fit_within(Size::square(50.)), Size::new(25., 50.));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let size = Size::new(1920., 1080.5);
+        assert_eq!(Size::from_bytes(&size.to_bytes()), Some(size));
+        assert_eq!(Size::from_bytes(&[0; 9]), None);
+    }
+
+    #[test]
+    fn scalar_and_component_wise() {
+        let size = Size::new(200., 100.);
+
+        assert_eq!(size * 2., Size::new(400., 200.));
+        assert_eq!(size / 4., Size::new(50., 25.));
+        assert_eq!(size * Size::new(0.5, 3.), Size::new(100., 300.));
+
+        // normalizing against the screen, like the renderer does
+        let screen = Size::new(800., 400.);
+        assert_eq!(size / screen, Size::new(0.25, 0.25));
+
+        let mut size = size;
+        size *= 2.;
+        size /= 8.;
+        assert_eq!(size, Size::new(50., 25.));
     }
 }
@@ -59,6 +59,16 @@ impl Vec2f {
         }
     }
 
+    /// The vector as a position relative to the origin
+    pub const fn to_point(self) -> crate::point::Point {
+        crate::point::Point::new(self.x, self.y)
+    }
+
+    /// x as the width & y as the height
+    pub const fn to_size(self) -> crate::size::Size {
+        crate::size::Size::new(self.x, self.y)
+    }
+
     pub fn vec2u(self) -> Vec2u {
         Vec2u {
             x: self.x.round() as u32,
@@ -242,6 +252,12 @@ impl From<crate::point::Point> for Vec2f {
     }
 }
 
+impl From<crate::size::Size> for Vec2f {
+    fn from(value: crate::size::Size) -> Self {
+        value.to_vec2f()
+    }
+}
+
 /*
 #########################################################
 #                                                       #
@@ -355,3 +371,23 @@ impl PartialOrd for Vec2u {
             .map(|ord| ord.then(self.y.cmp(&other.y)))
     }
 }
+
+#[cfg(test)]
+mod vector_test {
+    use crate::{Point, Size};
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip() {
+        let point = Point::new(3., 4.);
+        assert_eq!(point.to_vec2f(), Vec2f::new(3., 4.));
+        assert_eq!(point.to_vec2f().to_point(), point);
+
+        let size = Size::new(30., 40.);
+        assert_eq!(size.to_vec2f(), Vec2f::new(30., 40.));
+        assert_eq!(size.to_vec2f().to_size(), size);
+
+        assert_eq!(Vec2f::from(size), size.to_vec2f());
+        assert_eq!(Size::from(Vec2f::new(1., 2.)), Size::new(1., 2.));
+    }
+}
@@ -29,9 +29,9 @@ fn root() -> impl IntoView {
 
 
 fn main() -> ApliteResult {
-    let config = AppConfig {
-        window_inner_size: (500, 700).into(),
-    };
+    let config = AppConfig::default()
+        .title("demo")
+        .inner_size((500, 700).into());
 
     Aplite::new(config, root).launch()
 }
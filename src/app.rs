@@ -1,37 +1,139 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
-use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::window::{Window, WindowAttributes, WindowId};
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, StartCause, WindowEvent};
 use winit::application::ApplicationHandler;
 
-use aplite_renderer::Renderer;
+use aplite_renderer::{Renderer, RendererConfig};
 use aplite_future::block_on;
 use aplite_types::Size;
 
+use crate::callback::WidgetEvent;
 use crate::prelude::ApliteResult;
 use crate::context::Context;
 use crate::error::ApliteError;
+use crate::theme::Theme;
 use crate::view::IntoView;
 
 pub struct AppConfig {
     pub window_inner_size: Size,
+    pub title: String,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub theme: Option<Theme>,
+    pub max_fps: Option<u32>,
+    pub atlas_size: Size,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             window_inner_size: Size::new(400., 400.),
+            title: "aplite".to_string(),
+            resizable: true,
+            decorations: true,
+            theme: None,
+            max_fps: None,
+            atlas_size: RendererConfig::default().atlas_size,
         }
     }
 }
 
+impl AppConfig {
+    pub fn title(self, title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..self
+        }
+    }
+
+    /// Initial logical size of the window
+    pub fn inner_size(self, window_inner_size: Size) -> Self {
+        Self {
+            window_inner_size,
+            ..self
+        }
+    }
+
+    pub fn resizable(self, resizable: bool) -> Self {
+        Self {
+            resizable,
+            ..self
+        }
+    }
+
+    pub fn decorations(self, decorations: bool) -> Self {
+        Self {
+            decorations,
+            ..self
+        }
+    }
+
+    /// Widgets default to the colors of the theme, unless their style sets them explicitly.
+    /// The theme is held in a `Signal<Theme>` retrievable via `use_context`, setting it switches the theme live
+    pub fn theme(self, theme: Theme) -> Self {
+        Self {
+            theme: Some(theme),
+            ..self
+        }
+    }
+
+    /// Cap the redraw rate. Frames requested ahead of schedule are delayed until the next frame is due,
+    /// `0` removes the cap
+    pub fn max_fps(self, max_fps: u32) -> Self {
+        Self {
+            max_fps: Some(max_fps).filter(|fps| *fps > 0),
+            ..self
+        }
+    }
+
+    /// Initial size of the texture atlas holding the images, it grows on demand up to the device limit
+    pub fn atlas_size(self, atlas_size: Size) -> Self {
+        Self {
+            atlas_size,
+            ..self
+        }
+    }
+
+    pub(crate) fn renderer_config(&self) -> RendererConfig {
+        RendererConfig {
+            atlas_size: self.atlas_size,
+            ..RendererConfig::default()
+        }
+    }
+
+    pub(crate) fn context(&self) -> Context {
+        let mut cx = Context::new(self.window_inner_size);
+        if let Some(theme) = self.theme {
+            cx.set_theme(theme);
+        }
+        cx
+    }
+
+    pub(crate) fn window_attributes(&self) -> WindowAttributes {
+        WindowAttributes::default()
+            .with_title(&self.title)
+            .with_inner_size(LogicalSize::new(
+                self.window_inner_size.width,
+                self.window_inner_size.height,
+            ))
+            .with_resizable(self.resizable)
+            .with_decorations(self.decorations)
+    }
+}
+
 pub struct Aplite<IV: IntoView> {
     view: IV::View,
     cx: Context,
     renderer: Option<Renderer>,
     window: Option<Arc<Window>>,
+    config: AppConfig,
+    last_frame: Option<Instant>,
+    /// A redraw was held back by the frame limiter
+    frame_pending: bool,
 
     #[cfg(feature = "render_stats")]
     stats: aplite_stats::Stats,
@@ -42,16 +144,32 @@ impl<IV: IntoView> Aplite<IV> {
         Self {
             view: view().into_view(),
             renderer: None,
-            cx: Context::new(config.window_inner_size),
+            cx: config.context(),
             window: None,
+            config,
+            last_frame: None,
+            frame_pending: false,
 
             #[cfg(feature = "render_stats")]
             stats: aplite_stats::Stats::new(),
         }
     }
 
+    /// Make a value available to every widget via `use_context` before the first build
+    pub fn provide<T: 'static>(mut self, value: T) -> Self {
+        self.cx.provide(value);
+        self
+    }
+
+    /// Switch the active theme, widgets using theme defaults pick it up on the next build.
+    /// Widgets can switch it too, via the `Signal<Theme>` retrievable with `use_context`
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.cx.set_theme(theme);
+    }
+
     pub fn launch(mut self) -> ApliteResult {
         let event_loop = EventLoop::new()?;
+        self.cx.set_event_loop_proxy(event_loop.create_proxy());
         event_loop.run_app(&mut self)?;
 
         Ok(())
@@ -61,15 +179,12 @@ impl<IV: IntoView> Aplite<IV> {
         &mut self,
         event_loop: &ActiveEventLoop,
     ) -> Result<(), ApliteError> {
-        let window_attributes = WindowAttributes::default()
-            .with_inner_size(LogicalSize::new(
-                self.cx.window_rect.width,
-                self.cx.window_rect.height
-            ));
+        let window_attributes = self.config.window_attributes();
 
         let window = Arc::new(event_loop.create_window(window_attributes)?);
 
-        self.renderer = Some(block_on(Renderer::new(Arc::clone(&window)))?);
+        let renderer_config = self.config.renderer_config();
+        self.renderer = Some(block_on(Renderer::new(Arc::clone(&window), renderer_config))?);
 
         self.cx.build(&self.view);
         self.cx.layout(&self.view);
@@ -93,7 +208,11 @@ impl<IV: IntoView> Aplite<IV> {
 
     fn set_scale_factor(&mut self, scale_factor: f64) {
         if let Some(renderer) = self.renderer.as_mut() {
-            renderer.set_scale_factor(scale_factor);
+            renderer.set_scale_factor(scale_factor, |logical| {
+                self.cx.window_rect.set_size(logical);
+            });
+            self.cx.layout(&self.view);
+            self.cx.request_redraw();
         }
     }
 
@@ -108,6 +227,29 @@ impl<IV: IntoView> Aplite<IV> {
         self.cx.handle_click(state, button);
     }
 
+    fn handle_scroll(&mut self, delta: MouseScrollDelta) {
+        // roughly one line of text per notch
+        const LINE_HEIGHT: f32 = 20.;
+
+        let delta = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (x * LINE_HEIGHT, y * LINE_HEIGHT),
+            MouseScrollDelta::PixelDelta(pos) => {
+                let scale = self.renderer.as_ref().map_or(1., |renderer| renderer.scale_factor());
+                let logical = pos.to_logical::<f32>(scale);
+                (logical.x, logical.y)
+            },
+        };
+
+        self.cx.handle_event(WidgetEvent::Scroll(delta.into()));
+    }
+
+    fn handle_key(&mut self, event: KeyEvent) {
+        match event.state {
+            ElementState::Pressed => self.cx.handle_event(WidgetEvent::KeyDown(event.logical_key)),
+            ElementState::Released => self.cx.handle_event(WidgetEvent::KeyUp(event.logical_key)),
+        }
+    }
+
     fn handle_close_request(&mut self, window_id: &WindowId, event_loop: &ActiveEventLoop) {
         if let Some(window) = self.window.take_if(|w| w.id() == *window_id) {
             drop(window);
@@ -117,23 +259,63 @@ impl<IV: IntoView> Aplite<IV> {
     }
 
     // WARN: not sure if retained mode works like this
-    fn handle_redraw_request(&mut self, window_id: &WindowId, _: &ActiveEventLoop) {
+    fn handle_redraw_request(&mut self, window_id: &WindowId, event_loop: &ActiveEventLoop) {
         if let Some(window) = self.window.as_ref()
             && window.id() == *window_id
             && let Some(renderer) = self.renderer.as_mut()
         {
+            let now = Instant::now();
+
+            if let Some(max_fps) = self.config.max_fps
+                && let Some(last_frame) = self.last_frame
+            {
+                let deadline = next_frame_deadline(max_fps, last_frame);
+                if now < deadline {
+                    self.frame_pending = true;
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
+                    return;
+                }
+            }
+
+            self.last_frame = Some(now);
+
             #[cfg(feature = "render_stats")] let start = std::time::Instant::now();
 
             renderer.begin();
             self.cx.render(renderer);
-            renderer.finish(window);
-
-            #[cfg(feature = "render_stats")] self.stats.inc(start.elapsed());
+            match renderer.render_with_retry(Some(window)) {
+                Err(err) if err.is_recoverable() => window.request_redraw(),
+                Err(_) => event_loop.exit(),
+                Ok(()) => {}
+            }
+
+            #[cfg(feature = "render_stats")] {
+                self.stats.inc(start.elapsed());
+                self.stats.elements(renderer.element_count());
+            }
         }
     }
 }
 
+/// The earliest time the next frame may be drawn, given the time the last one was drawn
+fn next_frame_deadline(max_fps: u32, last_frame: Instant) -> Instant {
+    last_frame + Duration::from_secs(1) / max_fps.max(1)
+}
+
 impl<IV: IntoView> ApplicationHandler for Aplite<IV> {
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        if let StartCause::ResumeTimeReached { .. } = cause
+            && self.frame_pending
+        {
+            self.frame_pending = false;
+            event_loop.set_control_flow(ControlFlow::Wait);
+
+            if let Some(window) = self.window.as_ref() {
+                window.request_redraw();
+            }
+        }
+    }
+
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         self.initialize_window_and_renderer(event_loop)
             .unwrap_or_else(|_| event_loop.exit());
@@ -151,13 +333,23 @@ impl<IV: IntoView> ApplicationHandler for Aplite<IV> {
             WindowEvent::Resized(size) => self.handle_resize(size),
             WindowEvent::MouseInput { state, button, .. } => self.handle_click(state, button),
             WindowEvent::CursorMoved { position, .. } => self.handle_mouse_move(&window_id, position),
+            WindowEvent::MouseWheel { delta, .. } => self.handle_scroll(delta),
+            WindowEvent::KeyboardInput { event, .. } => self.handle_key(event),
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.set_scale_factor(scale_factor),
             _ => {}
         }
 
         let dirty = self.cx.rebuild(&self.view);
+        let requested = self.cx.take_redraw_request();
+
+        if let Some(window) = self.window.as_ref() && (dirty || requested) {
+            window.request_redraw();
+        }
+    }
 
-        if let Some(window) = self.window.as_ref() && dirty {
+    /// Woken up by a [`RedrawHandle`](crate::context::RedrawHandle)
+    fn user_event(&mut self, _: &ActiveEventLoop, _: ()) {
+        if let Some(window) = self.window.as_ref() && self.cx.take_redraw_request() {
             window.request_redraw();
         }
     }
@@ -172,8 +364,11 @@ pub trait Launch: IntoView {
         let app: Aplite<Self> = Aplite {
             view: self.into_view(),
             renderer: None,
-            cx: Context::new(config.window_inner_size),
+            cx: config.context(),
             window: None,
+            config,
+            last_frame: None,
+            frame_pending: false,
 
             #[cfg(feature = "render_stats")]
             stats: aplite_stats::Stats::new(),
@@ -184,3 +379,75 @@ pub trait Launch: IntoView {
 }
 
 impl<IV> Launch for IV where IV: IntoView {}
+
+#[cfg(test)]
+mod app_test {
+    use winit::dpi::Size as WinitSize;
+    use super::*;
+
+    #[test]
+    fn frame_deadline() {
+        let last_frame = Instant::now();
+
+        assert_eq!(next_frame_deadline(60, last_frame), last_frame + Duration::from_nanos(16_666_666));
+        assert_eq!(next_frame_deadline(30, last_frame), last_frame + Duration::from_nanos(33_333_333));
+        assert_eq!(next_frame_deadline(1, last_frame), last_frame + Duration::from_secs(1));
+
+        assert_eq!(AppConfig::default().max_fps(0).max_fps, None);
+        assert_eq!(AppConfig::default().max_fps(60).max_fps, Some(60));
+    }
+
+    #[test]
+    fn window_attributes_from_config() {
+        let config = AppConfig::default()
+            .title("counter")
+            .inner_size(Size::new(640., 480.))
+            .resizable(false)
+            .decorations(false);
+
+        let attributes = config.window_attributes();
+
+        assert_eq!(attributes.title, "counter");
+        assert_eq!(attributes.inner_size, Some(WinitSize::Logical(LogicalSize::new(640., 480.))));
+        assert!(!attributes.resizable);
+        assert!(!attributes.decorations);
+    }
+
+    #[test]
+    fn provide_and_use_context() {
+        use crate::widget::test_util::ReadContext;
+
+        let read = std::rc::Rc::new(std::cell::Cell::new(None));
+        let view = {
+            let read = read.clone();
+            move || ReadContext(read.clone())
+        };
+
+        let mut app = Aplite::new(AppConfig::default(), view).provide(69u32);
+        app.cx.build(&app.view);
+
+        assert_eq!(read.get(), Some(69));
+    }
+
+    #[test]
+    fn set_theme_at_runtime() {
+        use crate::widget::{ButtonElement, Renderable, button};
+
+        let mut app = Aplite::new(AppConfig::default(), || button("", || {}));
+        let background = |cx: &Context| {
+            let element = cx.elements[0].as_ref();
+            assert_eq!(element.type_id(), std::any::TypeId::of::<ButtonElement>());
+            unsafe { &*(element as *const dyn Renderable as *const ButtonElement) }.background
+        };
+
+        app.set_theme(Theme::LIGHT);
+        app.cx.build(&app.view);
+        assert_eq!(background(&app.cx), Theme::LIGHT.accent);
+        // redraw phase
+        app.cx.build(&app.view);
+
+        app.set_theme(Theme::GRUVBOX_DARK);
+        assert!(app.cx.build(&app.view));
+        assert_eq!(background(&app.cx), Theme::GRUVBOX_DARK.accent);
+    }
+}
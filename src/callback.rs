@@ -0,0 +1,50 @@
+use std::cell::RefCell;
+
+use aplite_types::Vec2f;
+use winit::keyboard::Key;
+
+use crate::cursor::MouseButton;
+use crate::widget::Renderable;
+
+/// Pointer and keyboard input routed to the hovered widget
+#[derive(Debug, Clone, PartialEq)]
+pub enum WidgetEvent {
+    PointerEnter,
+    PointerLeave,
+    /// Cursor position relative to the top-left of the widget
+    PointerMove(Vec2f),
+    /// Cursor position relative to the top-left of the pressed widget,
+    /// sent to it while dragging, also when the cursor is outside of it
+    Drag(Vec2f),
+    PointerDown(MouseButton),
+    PointerUp(MouseButton),
+    /// Cursor position relative to the top-left of the widget,
+    /// sent when the left button is pressed and released over it
+    Click(Vec2f),
+    /// Scroll delta in logical pixels
+    Scroll(Vec2f),
+    KeyDown(Key),
+    KeyUp(Key),
+}
+
+pub(crate) trait EventHandler {
+    fn handle(&self, element: &mut dyn Renderable, event: WidgetEvent);
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) struct EventFn<E>(RefCell<Box<dyn FnMut(&mut E, WidgetEvent)>>);
+
+impl<E> EventFn<E> {
+    pub(crate) fn new(f: impl FnMut(&mut E, WidgetEvent) + 'static) -> Self {
+        Self(RefCell::new(Box::new(f)))
+    }
+}
+
+impl<E: Renderable> EventHandler for EventFn<E> {
+    fn handle(&self, element: &mut dyn Renderable, event: WidgetEvent) {
+        if element.type_id() == std::any::TypeId::of::<E>() {
+            let element = unsafe { &mut *(element as *mut dyn Renderable as *mut E) };
+            (self.0.borrow_mut())(element, event)
+        }
+    }
+}
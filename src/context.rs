@@ -1,11 +1,19 @@
+use std::any::TypeId;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use rustc_hash::{FxHashMap, FxHasher};
+use aplite_reactive::*;
 use aplite_renderer::Renderer;
 use aplite_types::{Rect, Size, Vec2f};
+use winit::event_loop::EventLoopProxy;
 
 use crate::layout::{AlignH, AlignV, Axis, LayoutRules, Padding, Spacing};
-use crate::cursor::{Cursor, EmittedClickEvent, MouseAction, MouseButton};
+use crate::callback::{EventHandler, WidgetEvent};
+use crate::cursor::{Cursor, EmittedClickEvent, EventTarget, MouseAction, MouseButton};
+use crate::theme::Theme;
+use crate::view::WidgetId;
 use crate::widget::{Renderable, Widget};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -16,26 +24,40 @@ pub(crate) struct ViewPath(pub(crate) Vec<u32>);
 
 pub struct BuildCx<'a> {
     view_path: &'a mut ViewPath,
+    contexts: &'a mut ContextMap,
     view_ids: &'a mut FxHashMap<PathId, ViewId>,
     elements: &'a mut Vec<Box<dyn Renderable>>,
-    next_id: u64,
+    order: &'a mut Vec<ViewId>,
+    kept_alive: &'a mut Vec<ViewId>,
+    free_ids: &'a mut Vec<ViewId>,
+    layout_cache: &'a mut Vec<Option<LayoutCache>>,
+    z_indices: &'a mut Vec<i32>,
+    parents: &'a mut Vec<Option<ViewId>>,
 }
 
 pub struct LayoutCx<'a> {
     view_path: &'a mut ViewPath,
+    contexts: &'a ContextMap,
     view_ids: &'a mut FxHashMap<PathId, ViewId>,
     elements: &'a mut Vec<Box<dyn Renderable>>,
     layout_nodes: &'a mut Vec<Rect>,
+    layout_cache: &'a mut Vec<Option<LayoutCache>>,
+    free_ids: &'a [ViewId],
+    depth: u32,
     pub(crate) bound: Rect,
     pub(crate) rules: LayoutRules,
 }
 
 pub struct CursorCx<'a> {
     view_path: &'a mut ViewPath,
+    contexts: &'a ContextMap,
     view_ids: &'a mut FxHashMap<PathId, ViewId>,
     elements: &'a mut Vec<Box<dyn Renderable>>,
     cursor: &'a mut Cursor,
     layout_nodes: &'a mut Vec<Rect>,
+    free_ids: &'a [ViewId],
+    z_indices: &'a [i32],
+    subtree_z_indices: &'a [i32],
 }
 
 pub struct Elements {
@@ -53,26 +75,127 @@ pub struct LayoutNodes {
     rules: FxHashMap<PathId, LayoutRules>,
 }
 
+/// The bound & rules a view was laid out with, and the bound its parent continues with afterwards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LayoutCache {
+    bound: Rect,
+    rules: LayoutRules,
+    advanced: Rect,
+    /// How many [`LayoutCx::layout_child`] calls deep the entry was made. Tuples & iterables reuse
+    /// their parent's path for the first child, so both share the same id
+    depth: u32,
+}
+
+/// Schedules a repaint from outside the reactive system, e.g. from a background thread.
+/// Available to every widget via `use_context::<RedrawHandle>()`
+#[derive(Clone, Default)]
+pub struct RedrawHandle {
+    pending: Arc<AtomicBool>,
+    proxy: Option<EventLoopProxy<()>>,
+}
+
+impl RedrawHandle {
+    /// Mark the window as needing a repaint and wake up the event loop
+    pub fn request_redraw(&self) {
+        self.pending.store(true, Ordering::Release);
+        if let Some(proxy) = self.proxy.as_ref() {
+            let _ = proxy.send_event(());
+        }
+    }
+}
+
+/// Values provided by a view, keyed by the path of the provider. App-level values live at the root path
+type ContextMap = FxHashMap<(PathId, TypeId), Box<dyn std::any::Any>>;
+
 pub(crate) struct Context {
     pub(crate) elements: Vec<Box<dyn Renderable>>,
     pub(crate) layout_nodes: Vec<Rect>,
+    /// Layout of the views whose subtree hasn't changed since they were laid out, indexed by [`ViewId`]
+    layout_cache: Vec<Option<LayoutCache>>,
+    /// Ids of the elements visited by the last build, in build order
+    pub(crate) order: Vec<ViewId>,
+    /// [`order`](Self::order) stably sorted by z index, the last one is painted on top
+    pub(crate) paint_order: Vec<ViewId>,
+    /// Z index of each element added up with its ancestors', indexed by [`ViewId`]
+    z_indices: Vec<i32>,
+    /// The highest of [`z_indices`](Self::z_indices) within the subtree of each element, used to hit-test siblings
+    subtree_z_indices: Vec<i32>,
+    /// Nearest ancestor with an element of each element, indexed by [`ViewId`]
+    parents: Vec<Option<ViewId>>,
+    /// Ids of detached views which keep their slots without being rendered
+    kept_alive: Vec<ViewId>,
+    /// Slots of removed views, reused by views added later
+    free_ids: Vec<ViewId>,
     view_ids: FxHashMap<PathId, ViewId>,
     view_path: ViewPath,
+    contexts: ContextMap,
     cursor: Cursor,
     pub(crate) window_rect: Rect,
     redraw_phase: bool,
+    redraw: RedrawHandle,
 }
 
 impl Context {
     pub(crate) fn new(size: Size) -> Self {
-        Self {
+        let mut cx = Self {
             elements: Vec::new(),
             layout_nodes: Vec::new(),
+            layout_cache: Vec::new(),
+            order: Vec::new(),
+            paint_order: Vec::new(),
+            z_indices: Vec::new(),
+            subtree_z_indices: Vec::new(),
+            parents: Vec::new(),
+            kept_alive: Vec::new(),
+            free_ids: Vec::new(),
             view_ids: FxHashMap::default(),
             view_path: ViewPath::new(),
+            contexts: ContextMap::default(),
             cursor: Cursor::default(),
             window_rect: Rect::from_size(size),
             redraw_phase: false,
+            redraw: RedrawHandle::default(),
+        };
+        cx.provide(cx.redraw.clone());
+        cx
+    }
+
+    /// Let [`RedrawHandle`]s wake up the event loop
+    pub(crate) fn set_event_loop_proxy(&mut self, proxy: EventLoopProxy<()>) {
+        self.redraw.proxy = Some(proxy);
+        self.provide(self.redraw.clone());
+    }
+
+    /// Schedule a repaint, even if nothing reactive has changed
+    pub fn request_redraw(&self) {
+        self.redraw.request_redraw();
+    }
+
+    /// Whether a repaint was requested since the last call, resetting the request
+    pub(crate) fn take_redraw_request(&self) -> bool {
+        self.redraw.pending.swap(false, Ordering::AcqRel)
+    }
+
+    /// Insert a value retrievable from any widget via `use_context`, replacing the previous value of the same type
+    pub(crate) fn provide<T: 'static>(&mut self, value: T) {
+        let root = ViewPath::new().get_path_id();
+        self.contexts.insert((root, TypeId::of::<T>()), Box::new(value));
+    }
+
+    /// The signal holding the active theme, if any was set
+    pub(crate) fn theme(&self) -> Option<Signal<Theme>> {
+        let root = ViewPath::new().get_path_id();
+        self.contexts
+            .get(&(root, TypeId::of::<Signal<Theme>>()))
+            .and_then(|value| value.downcast_ref::<Signal<Theme>>())
+            .copied()
+    }
+
+    /// Switch the active theme. Widgets using theme defaults pick it up on the next build
+    pub(crate) fn set_theme(&mut self, theme: Theme) {
+        match self.theme() {
+            Some(signal) => signal.set(theme),
+            None => self.provide(Signal::new(theme)),
         }
     }
 
@@ -82,18 +205,68 @@ impl Context {
             return false;
         }
 
+        // providers insert their values again while building, dropping the stale ones
+        let root = ViewPath::new().get_path_id();
+        self.contexts.retain(|(path_id, _), _| *path_id == root);
+
+        let prev_order = std::mem::take(&mut self.order);
+        self.kept_alive.clear();
+
         let mut cx = BuildCx::new(self);
         let dirty = cx.with_id(0, |cx| view.build(cx));
 
-        let count = cx.next_id as usize;
-        let len = self.elements.len();
-        self.elements.truncate(count);
+        let changed = self.order != prev_order;
+        if changed {
+            self.remove_unvisited();
+            self.layout_cache.clear();
+        }
+
+        let dirty = dirty || changed;
+        if dirty {
+            self.paint_order.clone_from(&self.order);
+            self.paint_order.sort_by_key(|id| self.z_indices[id.0 as usize]);
+            self.update_subtree_z_indices();
+        }
 
-        let dirty = dirty || count != len;
         self.redraw_phase = dirty;
         dirty
     }
 
+    /// Raise the z index of every element to the highest one in its subtree, so a raised descendant
+    /// takes its ancestors along when they are hit-tested against their siblings
+    fn update_subtree_z_indices(&mut self) {
+        self.subtree_z_indices.clone_from(&self.z_indices);
+
+        // descendants are built after their ancestors
+        for id in self.order.iter().rev() {
+            if let Some(parent) = self.parents[id.0 as usize] {
+                let z_index = self.subtree_z_indices[id.0 as usize];
+                let parent = &mut self.subtree_z_indices[parent.0 as usize];
+                *parent = (*parent).max(z_index);
+            }
+        }
+    }
+
+    /// Free the slots of views which weren't visited by the last build, so their ids can be reused
+    fn remove_unvisited(&mut self) {
+        let mut visited = vec![false; self.elements.len()];
+        self.order
+            .iter()
+            .chain(self.kept_alive.iter())
+            .for_each(|id| visited[id.0 as usize] = true);
+
+        self.view_ids.retain(|_, id| visited[id.0 as usize]);
+
+        for (index, element) in self.elements.iter_mut().enumerate() {
+            let id = ViewId(index as u64);
+
+            if !visited[index] && !self.free_ids.contains(&id) {
+                *element = Box::new(());
+                self.free_ids.push(id);
+            }
+        }
+    }
+
     pub fn rebuild<T: Widget>(&mut self, view: &T) -> bool {
         if self.build(view) {
             self.layout(view);
@@ -110,27 +283,77 @@ impl Context {
             align_h: AlignH::Left,
             align_v: AlignV::Top,
             spacing: Spacing(0),
+            grid: None,
         };
 
         let mut cx = LayoutCx::new(self, rules, self.window_rect);
-        cx.with_id(0, |cx| view.layout(cx));
+        cx.layout_child(0, view);
 
         let len = self.elements.len();
         self.layout_nodes.truncate(len);
+        self.layout_cache.truncate(len);
+    }
+
+    /// The rect computed by the last layout pass in logical coordinates, [`None`] if the view has been removed
+    pub fn bounds_of(&self, id: ViewId) -> Option<Rect> {
+        bounds_of(&self.layout_nodes, &self.free_ids, id)
     }
 
     pub(crate) fn handle_mouse_move<T: Widget>(&mut self, pos: impl Into<Vec2f>, view: &T) {
         self.cursor.hover.pos = pos.into();
+        let prev = self.cursor.hover.curr.take();
 
         #[cfg(feature = "cursor_stats")] let start = std::time::Instant::now();
         let mut cx = CursorCx::new(self);
         cx.with_id(0, |cx| view.detect_hover(cx));
         #[cfg(feature = "cursor_stats")] eprint!("{:?}     \r", start.elapsed());
 
+        let change = self.cursor.process_hover_change(prev);
+
+        if let Some(callback) = change.on_leave {
+            unsafe { (*callback.as_ptr())() }
+        }
+        self.dispatch(change.left, WidgetEvent::PointerLeave);
+
+        if let Some(callback) = change.on_enter {
+            unsafe { (*callback.as_ptr())() }
+        }
+        self.dispatch(change.entered, WidgetEvent::PointerEnter);
+
+        if let Some((id, _)) = self.cursor.hover.target {
+            self.handle_event(WidgetEvent::PointerMove(self.pointer_pos(id)));
+        }
         self.handle_drag();
     }
 
+    /// Cursor position relative to the top-left of the widget
+    fn pointer_pos(&self, id: ViewId) -> Vec2f {
+        self.bounds_of(id)
+            .map_or(self.cursor.hover.pos, |rect| self.cursor.position_in(&rect))
+    }
+
+    /// Send the event to the hovered widget's `on_event` handler, if any
+    pub(crate) fn handle_event(&mut self, event: WidgetEvent) {
+        self.dispatch(self.cursor.hover.target, event);
+    }
+
+    fn dispatch(&mut self, target: Option<EventTarget>, event: WidgetEvent) {
+        if let Some((id, handler)) = target
+            && let Some(element) = self.elements.get_mut(id.0 as usize)
+        {
+            // the handler may resize the element, and its ancestors aren't known from here
+            self.layout_cache.clear();
+            unsafe { (*handler.as_ptr()).handle(element.as_mut(), event) }
+        }
+    }
+
     pub(crate) fn handle_drag(&mut self) {
+        if self.cursor.is_dragging()
+            && let Some((id, _)) = self.cursor.captured.target
+        {
+            self.dispatch(self.cursor.captured.target, WidgetEvent::Drag(self.pointer_pos(id)));
+        }
+
         if self.cursor.is_dragging()
             && let Some(captured) = self.cursor.captured.id
             && !self.cursor.hover.curr.is_some_and(|id| id == captured)
@@ -149,11 +372,27 @@ impl Context {
         action: impl Into<MouseAction>,
         button: impl Into<MouseButton>
     ) {
-        match self.cursor.process_click_event(action.into(), button.into()) {
+        let (action, button) = (action.into(), button.into());
+
+        match action {
+            MouseAction::Pressed => self.handle_event(WidgetEvent::PointerDown(button)),
+            MouseAction::Released => self.handle_event(WidgetEvent::PointerUp(button)),
+        }
+
+        // pressed and released over the same widget
+        if action == MouseAction::Released
+            && button == MouseButton::Left
+            && let Some((id, handler)) = self.cursor.captured.target
+            && self.cursor.hover.curr == Some(id)
+        {
+            self.dispatch(Some((id, handler)), WidgetEvent::Click(self.pointer_pos(id)));
+        }
+
+        match self.cursor.process_click_event(action, button) {
             EmittedClickEvent::Captured(id) => {
-                let node = &self.layout_nodes[id.0 as usize];
-                let pos = node.vec2f();
-                self.cursor.click.offset = self.cursor.click.pos - pos;
+                if let Some(bounds) = self.bounds_of(id) {
+                    self.cursor.click.offset = self.cursor.click.pos - bounds.vec2f();
+                }
             },
             EmittedClickEvent::TriggerCallback(callback) => {
                 unsafe {
@@ -167,13 +406,18 @@ impl Context {
 
     pub(crate) fn render(&self, renderer: &mut Renderer) {
         let mut scene = renderer.scene();
-        self.layout_nodes
+        self.paint_order
             .iter()
-            .zip(&self.elements)
-            .for_each(|(rect, element)| element.render(rect, &mut scene));
+            .map(|id| id.0 as usize)
+            .for_each(|index| self.elements[index].render(&self.layout_nodes[index], &mut scene));
     }
 }
 
+fn bounds_of(layout_nodes: &[Rect], free_ids: &[ViewId], id: ViewId) -> Option<Rect> {
+    if free_ids.contains(&id) { return None }
+    layout_nodes.get(id.0 as usize).copied()
+}
+
 macro_rules! impl_context {
     ($cx:ident<$lifetime:lifetime>) => {
         impl<$lifetime> $cx<$lifetime> {
@@ -202,8 +446,20 @@ macro_rules! impl_context {
                     .and_then(|id| self.view_ids.get(&id))
             }
 
-            pub fn get_z_index(&self) -> u32 {
-                self.view_path.0.len() as u32
+            /// Retrieve a clone of the value provided by the nearest ancestor via [`BuildCx::provide_context`],
+            /// falling back to the one provided via [`Aplite::provide`](crate::app::Aplite::provide)
+            pub fn use_context<T: Clone + 'static>(&self) -> Option<T> {
+                let type_id = TypeId::of::<T>();
+                self.view_path
+                    .ancestors()
+                    .find_map(|path_id| self.contexts.get(&(path_id, type_id)))
+                    .and_then(|value| value.downcast_ref::<T>())
+                    .cloned()
+            }
+
+            /// The active theme, read from the theme signal provided by the app
+            pub fn theme(&self) -> Option<Theme> {
+                self.use_context::<Signal<Theme>>().map(|theme| theme.get())
             }
 
             pub fn get_element<S: Renderable + 'static>(&self) -> Option<&S> {
@@ -254,42 +510,96 @@ impl<'a> BuildCx<'a> {
     pub(crate) fn new(cx: &'a mut Context) -> Self {
         Self {
             view_path: &mut cx.view_path,
+            contexts: &mut cx.contexts,
             view_ids: &mut cx.view_ids,
             elements: &mut cx.elements,
-            next_id: 0,
+            order: &mut cx.order,
+            kept_alive: &mut cx.kept_alive,
+            free_ids: &mut cx.free_ids,
+            layout_cache: &mut cx.layout_cache,
+            z_indices: &mut cx.z_indices,
+            parents: &mut cx.parents,
         }
     }
 
+    /// Provide a value to every descendant of the current view, retrievable via `use_context`.
+    /// Values are meant to be cheap cloneable handles, like signals
+    pub fn provide_context<T: Clone + 'static>(&mut self, value: T) {
+        let path_id = self.view_path.get_path_id();
+        self.contexts.insert((path_id, TypeId::of::<T>()), Box::new(value));
+    }
+
     #[must_use]
     pub fn add_or_update_element<R: Renderable + 'static>(&mut self, element: R) -> bool {
         let id = self.get_or_create_id();
+        self.set_z_index(id, element.z_index());
 
         match self.elements.get_mut(id.0 as usize) {
             Some(exist) => if exist.equal(&element) {
-                false
+                return false
             } else {
                 let _ = std::mem::replace(exist, Box::new(element));
-                true
             },
-            None => {
-                self.elements.push(Box::new(element));
-                true
+            None => self.elements.push(Box::new(element)),
+        }
+
+        self.invalidate_layout();
+        true
+    }
+
+    fn set_z_index(&mut self, id: ViewId, z_index: i32) {
+        let parent = self.view_path
+            .ancestors()
+            .skip(1)
+            .find_map(|path_id| self.view_ids.get(&path_id))
+            .copied();
+
+        let index = id.0 as usize;
+        if index >= self.z_indices.len() {
+            self.z_indices.resize(index + 1, 0);
+            self.parents.resize(index + 1, None);
+        }
+        self.z_indices[index] = parent.map_or(0, |parent| self.z_indices[parent.0 as usize]) + z_index;
+        self.parents[index] = parent;
+    }
+
+    /// Drop the cached layout of the current view and its ancestors
+    fn invalidate_layout(&mut self) {
+        for path_id in self.view_path.ancestors() {
+            if let Some(id) = self.view_ids.get(&path_id)
+                && let Some(cache) = self.layout_cache.get_mut(id.0 as usize)
+            {
+                *cache = None;
             }
         }
     }
 
+    /// Build with `f`, returning the ids of the views it built
+    pub(crate) fn track<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> (R, Vec<ViewId>) {
+        let start = self.order.len();
+        let res = f(self);
+        (res, self.order[start..].to_vec())
+    }
+
+    /// Keep the slots of detached views alive without rendering them, so they can be attached again
+    pub(crate) fn keep_alive(&mut self, ids: &[ViewId]) {
+        self.kept_alive.extend_from_slice(ids);
+    }
+
     fn get_or_create_id(&mut self) -> ViewId {
         let path_id = self.view_path.get_path_id();
 
         let view_id = if let Some(view_id) = self.view_ids.get(&path_id) {
             *view_id
         } else {
-            let view_id = ViewId(self.next_id);
+            let view_id = self.free_ids
+                .pop()
+                .unwrap_or(ViewId(self.elements.len() as u64));
             self.view_ids.insert(path_id, view_id);
             view_id
         };
 
-        self.next_id += 1;
+        self.order.push(view_id);
         view_id
     }
 }
@@ -306,9 +616,13 @@ impl<'a> LayoutCx<'a> {
     pub(crate) fn new(cx: &'a mut Context, rules: LayoutRules, bound: Rect) -> Self {
         Self {
             view_path: &mut cx.view_path,
+            contexts: &cx.contexts,
             view_ids: &mut cx.view_ids,
             elements: &mut cx.elements,
             layout_nodes: &mut cx.layout_nodes,
+            layout_cache: &mut cx.layout_cache,
+            free_ids: &cx.free_ids,
+            depth: 0,
             rules,
             bound,
         }
@@ -321,22 +635,74 @@ impl<'a> LayoutCx<'a> {
     ) -> Self {
         Self {
             view_path: prev.view_path,
+            contexts: prev.contexts,
             view_ids: prev.view_ids,
             elements: prev.elements,
             layout_nodes: prev.layout_nodes,
+            layout_cache: prev.layout_cache,
+            free_ids: prev.free_ids,
+            depth: prev.depth,
             bound,
             rules,
         }
     }
 
+    /// Lay `widget` out at `id_path`. A view whose subtree hasn't changed since it was last laid out
+    /// with the same bound & rules keeps its rects, and only moves the bound on as it did back then
+    pub fn layout_child<W: Widget + ?Sized>(&mut self, id_path: u32, widget: &W) {
+        self.with_id(id_path, |cx| {
+            let Some(index) = cx.get_id().map(|id| id.0 as usize) else {
+                return widget.layout(cx)
+            };
+
+            let depth = cx.depth;
+
+            if let Some(Some(cache)) = cx.layout_cache.get(index)
+                && cache.depth == depth
+                && cache.bound == cx.bound
+                && cache.rules == cx.rules
+            {
+                cx.bound = cache.advanced;
+                return
+            }
+
+            if index >= cx.layout_cache.len() {
+                cx.layout_cache.resize(index + 1, None);
+            }
+            cx.layout_cache[index] = None;
+
+            let (bound, rules) = (cx.bound, cx.rules);
+            cx.depth += 1;
+            widget.layout(cx);
+            cx.depth -= 1;
+
+            // a deeper entry means the id belongs to a child, and this widget only passed its path down
+            if cx.layout_cache[index].is_none() {
+                cx.layout_cache[index] = Some(LayoutCache { bound, rules, advanced: cx.bound, depth });
+            }
+        })
+    }
+
+    /// [`layout_child`](Self::layout_child) for the single child of a widget without a node of its own,
+    /// which takes the first cell when laid out in a grid
+    pub(crate) fn layout_only_child<W: Widget + ?Sized>(&mut self, id_path: u32, widget: &W) {
+        if let Some(grid) = self.rules.grid {
+            let (bound, rules) = (self.bound, self.rules);
+            LayoutCx::derive(self, rules, grid.cell(bound, 0, 1)).layout_child(id_path, widget);
+        } else {
+            self.layout_child(id_path, widget);
+        }
+    }
+
     pub fn set_node(&mut self, rect: Rect) {
         let id = self.get_id().copied().unwrap();
 
-        if let Some(r) = self.layout_nodes.get_mut(id.0 as usize) {
-            *r = rect;
-        } else {
-            self.layout_nodes.push(rect);
+        let index = id.0 as usize;
+        if index >= self.layout_nodes.len() {
+            self.layout_nodes.resize(index + 1, Rect::default());
         }
+
+        self.layout_nodes[index] = rect;
     }
 
     pub fn get_layout_node(&self) -> Option<&Rect> {
@@ -347,6 +713,17 @@ impl<'a> LayoutCx<'a> {
     pub fn get_available_space(&self) -> Size {
         self.bound.size()
     }
+
+    /// The rect of any view, e.g. to position an overlay next to it. Views laid out earlier in this pass
+    /// have their new rect, the others the one from the last pass. [`None`] if the view has been removed
+    pub fn bounds_of(&self, id: ViewId) -> Option<Rect> {
+        bounds_of(self.layout_nodes, self.free_ids, id)
+    }
+
+    /// [`bounds_of`](Self::bounds_of) the view identified by `id`, [`None`] until it is built
+    pub fn bounds_of_widget(&self, id: &WidgetId) -> Option<Rect> {
+        id.get().and_then(|id| self.bounds_of(id))
+    }
 }
 
 /*
@@ -361,10 +738,14 @@ impl<'a> CursorCx<'a> {
     pub(crate) fn new(cx: &'a mut Context) -> Self {
         Self {
             view_path: &mut cx.view_path,
+            contexts: &cx.contexts,
             view_ids: &mut cx.view_ids,
             elements: &mut cx.elements,
             layout_nodes: &mut cx.layout_nodes,
             cursor: &mut cx.cursor,
+            free_ids: &cx.free_ids,
+            z_indices: &cx.z_indices,
+            subtree_z_indices: &cx.subtree_z_indices,
         }
     }
 
@@ -377,6 +758,16 @@ impl<'a> CursorCx<'a> {
         &self.cursor.hover.pos
     }
 
+    /// The rect of any view computed by the last layout pass, [`None`] if the view has been removed
+    pub fn bounds_of(&self, id: ViewId) -> Option<Rect> {
+        bounds_of(self.layout_nodes, self.free_ids, id)
+    }
+
+    /// [`bounds_of`](Self::bounds_of) the view identified by `id`, [`None`] until it is built
+    pub fn bounds_of_widget(&self, id: &WidgetId) -> Option<Rect> {
+        id.get().and_then(|id| self.bounds_of(id))
+    }
+
     pub fn set_callback_on_click<F>(&mut self, callback: &F)
     where
         F: Fn() + 'static
@@ -390,11 +781,52 @@ impl<'a> CursorCx<'a> {
 
     pub fn set_id(&mut self) {
         self.cursor.hover.curr = self.get_id().copied();
+        self.cursor.hover.pending = Default::default();
+    }
+
+    /// Register the callbacks fired when the cursor enters or leaves the widget, call it after [`set_id`](Self::set_id)
+    pub fn set_callback_on_hover(
+        &mut self,
+        on_enter: Option<&(dyn Fn() + 'static)>,
+        on_leave: Option<&(dyn Fn() + 'static)>,
+    ) {
+        use std::ptr::NonNull;
+
+        self.cursor.hover.pending.on_enter = on_enter.map(NonNull::from_ref);
+        self.cursor.hover.pending.on_leave = on_leave.map(NonNull::from_ref);
+    }
+
+    /// Called when the widget is pressed and released with the right button, call it after [`set_id`](Self::set_id)
+    pub fn set_callback_on_secondary_click(&mut self, callback: &(dyn Fn() + 'static)) {
+        self.cursor.hover.pending.on_secondary_click = Some(std::ptr::NonNull::from_ref(callback));
+    }
+
+    /// Route every [`WidgetEvent`] to the handler while this widget is hovered, call it after [`set_id`](Self::set_id)
+    pub(crate) fn set_event_handler(&mut self, handler: &(dyn EventHandler + 'static)) {
+        self.cursor.hover.pending.on_event = Some(std::ptr::NonNull::from_ref(handler));
     }
 
     pub fn is_clicking(&self) -> bool {
         self.cursor.is_left_clicking()
     }
+
+    /// Offsets from `first_path_id` of `count` siblings in hit-test order: the one whose subtree reaches highest
+    /// first, so a raised descendant is hit before the later siblings of its ancestors
+    pub(crate) fn hit_order(&mut self, first_path_id: u32, count: usize) -> Vec<u32> {
+        let fallback = self.get_id()
+            .map(|id| self.z_indices[id.0 as usize])
+            .unwrap_or_default();
+
+        let mut offsets = (0..count as u32).rev().collect::<Vec<_>>();
+        offsets.sort_by_key(|offset| {
+            let z_index = self.with_id(first_path_id + offset, |cx| {
+                cx.get_id().and_then(|id| cx.subtree_z_indices.get(id.0 as usize).copied())
+            });
+            std::cmp::Reverse(z_index.unwrap_or(fallback))
+        });
+
+        offsets
+    }
 }
 
 /*
@@ -405,7 +837,7 @@ impl<'a> CursorCx<'a> {
 #########################################################
 */
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct PathId(u64);
 
 impl Hash for PathId {
@@ -425,6 +857,17 @@ impl ViewPath {
         PathId(hasher.finish())
     }
 
+    /// Path ids from the current path up to, and including, the root
+    fn ancestors(&self) -> impl Iterator<Item = PathId> + '_ {
+        (0..=self.0.len())
+            .rev()
+            .map(|len| {
+                let mut hasher = FxHasher::default();
+                Hash::hash_slice(&self.0[..len], &mut hasher);
+                PathId(hasher.finish())
+            })
+    }
+
     fn get_parent_path_id(&self) -> Option<PathId> {
         if self.0.len() <= 1 { return None; }
         let mut hasher = FxHasher::default();
@@ -432,3 +875,224 @@ impl ViewPath {
         Some(PathId(hasher.finish()))
     }
 }
+
+#[cfg(test)]
+mod context_test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use super::*;
+    use crate::widget::test_util::{Built, Probe, ReadContext};
+
+    struct Provider<W>(u32, W);
+    struct Wrapper<W>(W);
+
+    impl<W: Widget> Widget for Provider<W> {
+        fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+            cx.provide_context(self.0);
+            cx.with_id(0, |cx| self.1.build(cx))
+        }
+
+        fn layout(&self, _cx: &mut LayoutCx<'_>) {}
+
+        fn detect_hover(&self, _cx: &mut CursorCx<'_>) -> bool { false }
+    }
+
+    impl<W: Widget> Widget for Wrapper<W> {
+        fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+            cx.with_id(0, |cx| self.0.build(cx))
+        }
+
+        fn layout(&self, _cx: &mut LayoutCx<'_>) {}
+
+        fn detect_hover(&self, _cx: &mut CursorCx<'_>) -> bool { false }
+    }
+
+    #[test]
+    fn nested_use_context() {
+        let read = Rc::new(Cell::new(None));
+        let mut cx = Context::new((500, 500).into());
+
+        cx.build(&Wrapper(ReadContext(read.clone())));
+        assert_eq!(read.get(), None);
+
+        cx.provide(1u32);
+        cx.build(&Wrapper(ReadContext(read.clone())));
+        assert_eq!(read.get(), Some(1));
+
+        let view = Provider(69, Wrapper(ReadContext(read.clone())));
+        cx.build(&view);
+        assert_eq!(read.get(), Some(69));
+
+        // siblings outside of the provider still see the app-level value
+        cx.build(&Wrapper(Wrapper(ReadContext(read.clone()))));
+        assert_eq!(read.get(), Some(1));
+    }
+
+    #[test]
+    fn request_redraw_sets_pending() {
+        let cx = Context::new((500, 500).into());
+        assert!(!cx.take_redraw_request());
+
+        cx.request_redraw();
+        assert!(cx.take_redraw_request());
+        assert!(!cx.take_redraw_request());
+
+        // from another thread
+        let handle = cx.redraw.clone();
+        std::thread::spawn(move || handle.request_redraw()).join().unwrap();
+        assert!(cx.take_redraw_request());
+    }
+
+    #[test]
+    fn bounds_of_stacked_children() {
+        use aplite_types::Length;
+        use crate::widget::{button, vstack};
+
+        let sized = |width, height| {
+            button("", || {}).style(move |elem, _| {
+                elem.width = Length::Fixed(width);
+                elem.height = Length::Fixed(height);
+            })
+        };
+
+        let view = vstack((sized(100., 40.), sized(60., 30.)));
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        // stack, first button, its content, second button, its content
+        let (first, second) = (cx.order[1], cx.order[3]);
+
+        assert_eq!(cx.bounds_of(first), Some(Rect::new(0., 0., 100., 40.)));
+        assert_eq!(cx.bounds_of(second), Some(Rect::new(0., 40., 60., 30.)));
+        assert_eq!(cx.bounds_of(ViewId(99)), None);
+    }
+
+    /// Records the bounds of the first recorded view while laid out
+    struct Overlay(Built, Rc<Cell<Option<Rect>>>);
+
+    impl Widget for Overlay {
+        fn build(&self, _cx: &mut BuildCx<'_>) -> bool { false }
+
+        fn layout(&self, cx: &mut LayoutCx<'_>) {
+            let anchor = self.0.borrow()[0].1;
+            self.1.set(cx.bounds_of(anchor));
+        }
+
+        fn detect_hover(&self, _cx: &mut CursorCx<'_>) -> bool { false }
+    }
+
+    impl crate::view::IntoView for Overlay {
+        type View = Self;
+
+        fn into_view(self) -> Self::View {
+            self
+        }
+    }
+
+    #[test]
+    fn bounds_of_from_layout() {
+        use crate::widget::vstack;
+
+        let (built, anchored) = (Built::default(), Rc::new(Cell::new(None)));
+        let view = vstack((
+            Probe::new("anchor").height(Signal::new(30.)).record(&built),
+            Overlay(built.clone(), anchored.clone()),
+        ));
+
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        assert_eq!(anchored.get(), Some(Rect::new(0., 0., 10., 30.)));
+    }
+
+    #[test]
+    fn relayout_only_changed_subtree() {
+        use aplite_types::Length;
+        use crate::widget::vstack;
+
+        let (changed, unrelated) = (Signal::new(20.), Signal::new(20.));
+        let (changed_runs, unrelated_runs) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+
+        let view = vstack((
+            vstack(Probe::new("unrelated").height(unrelated).count_layouts(&unrelated_runs))
+                .style(|elem| elem.height = Length::Fixed(100.)),
+            Probe::new("changed").height(changed).count_layouts(&changed_runs),
+        ));
+
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+        assert_eq!((changed_runs.get(), unrelated_runs.get()), (1, 1));
+
+        let (changed_id, unrelated_id) = (cx.order[3], cx.order[2]);
+        let unrelated_rect = cx.bounds_of(unrelated_id);
+
+        // nothing changed
+        cx.build(&view);
+        cx.layout(&view);
+        assert_eq!((changed_runs.get(), unrelated_runs.get()), (1, 1));
+
+        changed.set(40.);
+        assert!(cx.build(&view));
+        cx.layout(&view);
+        assert_eq!((changed_runs.get(), unrelated_runs.get()), (2, 1));
+        assert_eq!(cx.bounds_of(changed_id).map(|rect| rect.height), Some(40.));
+        assert_eq!(cx.bounds_of(unrelated_id), unrelated_rect);
+
+        // a new bound lays everything out again
+        cx.window_rect = Rect::new(0., 0., 300., 300.);
+        cx.layout(&view);
+        assert_eq!((changed_runs.get(), unrelated_runs.get()), (3, 2));
+    }
+
+    #[test]
+    fn higher_z_index_on_top() {
+        use crate::widget::vstack;
+
+        // always covers the same square, so siblings overlap
+        let layer = |z_index| Probe::new("layer").node(Rect::new(0., 0., 50., 50.)).z_index(z_index);
+
+        let view = vstack((layer(1), layer(0)));
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        let (stack, raised, flat) = (cx.order[0], cx.order[1], cx.order[2]);
+        assert_eq!(cx.paint_order, [stack, flat, raised]);
+
+        cx.handle_mouse_move((10., 10.), &view);
+        assert_eq!(cx.cursor.hover.curr, Some(raised));
+
+        // equal z keeps the tree order, the later sibling is on top
+        let view = vstack((layer(0), layer(0)));
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+        assert_eq!(cx.paint_order, cx.order);
+
+        cx.handle_mouse_move((10., 10.), &view);
+        assert_eq!(cx.cursor.hover.curr, Some(cx.order[2]));
+    }
+
+    #[test]
+    fn raised_grandchild_over_uncle() {
+        use crate::widget::vstack;
+
+        let layer = |z_index| Probe::new("layer").node(Rect::new(0., 0., 50., 50.)).z_index(z_index);
+
+        // the raised layer is nested in the earlier sibling of the flat one
+        let view = vstack((vstack(layer(5)), layer(0)));
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        let (raised, uncle) = (cx.order[2], cx.order[3]);
+        assert_eq!(cx.paint_order.last(), Some(&raised));
+
+        cx.handle_mouse_move((10., 10.), &view);
+        assert_eq!(cx.cursor.hover.curr, Some(raised));
+        assert_ne!(cx.cursor.hover.curr, Some(uncle));
+    }
+}
@@ -1,9 +1,13 @@
 use std::ptr::NonNull;
 
-use aplite_types::Vec2f;
+use aplite_types::{Rect, Vec2f};
 
+use crate::callback::EventHandler;
 use crate::context::ViewId;
 
+/// The widget receiving [`WidgetEvent`](crate::callback::WidgetEvent)s, and its handler
+pub(crate) type EventTarget = (ViewId, NonNull<dyn EventHandler>);
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MouseAction {
     Pressed,
@@ -54,6 +58,30 @@ pub struct MouseState {
 pub struct MouseHover {
     pub(crate) pos: Vec2f,
     pub(crate) curr: Option<ViewId>,
+    /// Hover callbacks registered by the widget detected in the current pass
+    pub(crate) pending: HoverCallbacks,
+    /// Leave callback of the currently hovered widget, fired once the cursor moves out of it
+    pub(crate) on_leave: Option<NonNull<dyn Fn()>>,
+    /// Right click callback of the currently hovered widget
+    pub(crate) on_secondary_click: Option<NonNull<dyn Fn()>>,
+    pub(crate) target: Option<EventTarget>,
+}
+
+#[derive(Default, Debug)]
+pub struct HoverCallbacks {
+    pub(crate) on_enter: Option<NonNull<dyn Fn()>>,
+    pub(crate) on_leave: Option<NonNull<dyn Fn()>>,
+    pub(crate) on_secondary_click: Option<NonNull<dyn Fn()>>,
+    pub(crate) on_event: Option<NonNull<dyn EventHandler>>,
+}
+
+/// What changed after a hover pass, triggered in order: leave, then enter
+#[derive(Default)]
+pub(crate) struct HoverChange {
+    pub(crate) on_leave: Option<NonNull<dyn Fn()>>,
+    pub(crate) left: Option<EventTarget>,
+    pub(crate) on_enter: Option<NonNull<dyn Fn()>>,
+    pub(crate) entered: Option<EventTarget>,
 }
 
 #[derive(Default, Debug)]
@@ -66,6 +94,10 @@ pub struct MouseClick {
 pub struct MouseCapture {
     pub(crate) id: Option<ViewId>,
     pub(crate) callback: Option<NonNull<dyn Fn()>>,
+    /// Event handler of the pressed widget, receiving the drag even after the cursor left it
+    pub(crate) target: Option<EventTarget>,
+    /// Widget pressed with the right button, and its callback
+    pub(crate) secondary: Option<(ViewId, NonNull<dyn Fn()>)>,
 }
 
 #[derive(Default, Debug)]
@@ -91,6 +123,11 @@ impl Cursor {
         self.click.pos
     }
 
+    /// Cursor position relative to the top-left of `rect`
+    pub fn position_in(&self, rect: &Rect) -> Vec2f {
+        self.hover.pos - rect.vec2f()
+    }
+
     pub fn button(&self) -> MouseButton {
         self.state.button
     }
@@ -114,6 +151,8 @@ impl Cursor {
             (MouseAction::Pressed, MouseButton::Left) => {
                 self.click.pos = self.hover.pos;
                 self.captured.id = self.hover.curr;
+                self.captured.target = self.hover.target
+                    .filter(|(id, _)| self.captured.id.is_some_and(|captured| captured == *id));
 
                 if let Some(captured) = self.captured.id {
                     EmittedClickEvent::Captured(captured)
@@ -122,6 +161,8 @@ impl Cursor {
                 }
             },
             (MouseAction::Released, MouseButton::Left) => {
+                self.captured.target = None;
+
                 if let Some(id) = self.captured.id.take()
                     && self.hover.curr.is_some_and(|hovered| hovered == id)
                     && let Some(callback) = self.captured.callback.take()
@@ -131,10 +172,42 @@ impl Cursor {
                     EmittedClickEvent::NoOp
                 }
             },
+            (MouseAction::Pressed, MouseButton::Right) => {
+                self.captured.secondary = self.hover.curr.zip(self.hover.on_secondary_click);
+                EmittedClickEvent::NoOp
+            },
+            (MouseAction::Released, MouseButton::Right) => {
+                if let Some((id, callback)) = self.captured.secondary.take()
+                    && self.hover.curr.is_some_and(|hovered| hovered == id)
+                {
+                    EmittedClickEvent::TriggerCallback(callback)
+                } else {
+                    EmittedClickEvent::NoOp
+                }
+            },
             _ => EmittedClickEvent::NoOp,
         }
     }
 
+    /// Swap the leave callback and the event target when the hovered widget changed
+    pub(crate) fn process_hover_change(&mut self, prev: Option<ViewId>) -> HoverChange {
+        let pending = std::mem::take(&mut self.hover.pending);
+
+        if prev == self.hover.curr {
+            return HoverChange::default();
+        }
+
+        let entered = self.hover.curr.zip(pending.on_event);
+        self.hover.on_secondary_click = pending.on_secondary_click;
+
+        HoverChange {
+            on_leave: std::mem::replace(&mut self.hover.on_leave, pending.on_leave),
+            left: std::mem::replace(&mut self.hover.target, entered),
+            on_enter: pending.on_enter,
+            entered,
+        }
+    }
+
     pub(crate) fn is_dragging(&self) -> bool {
         self.is_left_clicking()
             && self.captured.id.is_some()
@@ -146,3 +219,21 @@ impl Cursor {
             && matches!(self.state.button, MouseButton::Left)
     }
 }
+
+#[cfg(test)]
+mod cursor_test {
+    use super::*;
+
+    #[test]
+    fn position_in_offset_rect() {
+        let mut cursor = Cursor::default();
+        cursor.hover.pos = Vec2f::new(130., 75.);
+
+        let rect = Rect::new(100., 50., 80., 40.);
+        assert_eq!(cursor.position_in(&rect), Vec2f::new(30., 25.));
+
+        // outside of the rect it goes negative
+        cursor.hover.pos = Vec2f::new(90., 40.);
+        assert_eq!(cursor.position_in(&rect), Vec2f::new(-10., -10.));
+    }
+}
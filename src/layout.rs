@@ -1,3 +1,4 @@
+use aplite_types::Rect;
 // use aplite_types::{Rect, Vec2f};
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,6 +32,9 @@ pub struct Padding {
     pub right: u8,
 }
 
+/// Outer spacing of a view, taken from the bound it's laid out in. Margins of adjacent siblings add up
+pub type Margin = Padding;
+
 impl Axis {
     pub fn is_vertical(&self) -> bool {
         matches!(self, Self::Vertical)
@@ -95,6 +99,40 @@ pub struct LayoutRules {
     pub align_h: AlignH,
     pub align_v: AlignV,
     pub spacing: Spacing,
+    /// When set, children are placed into grid cells instead of along the axis
+    pub grid: Option<GridRules>,
+}
+
+/// A fixed number of columns, children wrap into a new row once a row is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridRules {
+    pub columns: usize,
+    pub row_gap: Spacing,
+    pub column_gap: Spacing,
+}
+
+impl GridRules {
+    /// The cell of the `index`-th of `count` children, the cells share `bound` evenly
+    pub fn cell(&self, bound: Rect, index: usize, count: usize) -> Rect {
+        let columns = self.columns.max(1);
+        let rows = count.div_ceil(columns).max(1);
+
+        let column_gap = self.column_gap.0 as f32;
+        let row_gap = self.row_gap.0 as f32;
+
+        let width = (bound.width - column_gap * (columns - 1) as f32) / columns as f32;
+        let height = (bound.height - row_gap * (rows - 1) as f32) / rows as f32;
+
+        let column = (index % columns) as f32;
+        let row = (index / columns) as f32;
+
+        Rect::new(
+            bound.x + column * (width + column_gap),
+            bound.y + row * (height + row_gap),
+            width,
+            height,
+        )
+    }
 }
 
 // impl LayoutRules {
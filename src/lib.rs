@@ -5,6 +5,7 @@ mod cursor;
 mod error;
 mod layout;
 mod state;
+mod theme;
 mod view;
 mod widget;
 
@@ -20,25 +21,33 @@ pub mod prelude {
 
     // pub use crate::app::{Aplite, AppConfig};
     pub use crate::app::{Aplite, AppConfig, Launch};
-    pub use crate::context::{BuildCx, LayoutCx, CursorCx};
-    pub use crate::cursor::Cursor;
+    pub use crate::context::{BuildCx, LayoutCx, CursorCx, RedrawHandle};
+    pub use crate::callback::WidgetEvent;
+    pub use crate::cursor::{Cursor, MouseButton};
+    pub use crate::theme::Theme;
 
     pub use crate::layout::{
         Axis,
         Padding,
+        Margin,
         Spacing,
+        GridRules,
         AlignV,
         AlignH
     };
 
     pub use crate::widget::*;
 
+    pub use crate::context::ViewId;
+
     pub use crate::view::{
         IntoView,
         ToAnyView,
+        IdentifyView,
+        WidgetId,
     };
 
-    pub use aplite_types::theme;
+    pub use aplite_types::{easing, theme};
     pub use aplite_types::{Color, rgb, rgba};
 
     pub type ApliteResult = Result<(), crate::error::ApliteError>;
@@ -1,6 +1,19 @@
 #[derive(Debug, Clone, Copy)]
 pub struct BorderWidth(pub f32);
 
+#[derive(Debug, Clone, Copy)]
+pub struct FontSize(pub f32);
+
+/// Counter-clockwise rotation in radians, applied around the center of the element
+#[derive(Debug, Clone, Copy)]
+pub struct Rotation(pub f32);
+
+impl Rotation {
+    pub(crate) fn transform(&self) -> aplite_types::Matrix3x2 {
+        aplite_types::Matrix3x2::from_rotate_rad(self.0)
+    }
+}
+
 macro_rules! partial_eq {
     ($name:ident) => {
         impl PartialEq for $name {
@@ -14,3 +27,5 @@ macro_rules! partial_eq {
 }
 
 partial_eq!(BorderWidth);
+partial_eq!(Rotation);
+partial_eq!(FontSize);
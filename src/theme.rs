@@ -0,0 +1,37 @@
+use aplite_types::{Color, CornerRadius};
+use aplite_types::theme::{basic, gruvbox_dark};
+
+/// Palette consulted by widgets for the defaults of the style fields they don't set explicitly.
+/// Provided to every widget via [`AppConfig::theme`](crate::app::AppConfig::theme) or [`Aplite::set_theme`](crate::app::Aplite::set_theme)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Color,
+    pub foreground: Color,
+    pub accent: Color,
+    pub border: Color,
+    pub corner_radius: CornerRadius,
+}
+
+impl Theme {
+    pub const GRUVBOX_DARK: Self = Self {
+        background: gruvbox_dark::BG_0,
+        foreground: gruvbox_dark::FG_0,
+        accent: gruvbox_dark::GREEN_0,
+        border: gruvbox_dark::GREEN_1,
+        corner_radius: CornerRadius::splat(5),
+    };
+
+    pub const LIGHT: Self = Self {
+        background: basic::WHITE,
+        foreground: basic::BLACK,
+        accent: gruvbox_dark::BLUE_0,
+        border: gruvbox_dark::BLUE_1,
+        corner_radius: CornerRadius::splat(5),
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::GRUVBOX_DARK
+    }
+}
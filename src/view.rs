@@ -1,10 +1,12 @@
+use std::cell::Cell;
 use std::ptr::NonNull;
+use std::rc::Rc;
 
 use aplite_types::Rect;
 
 use crate::layout::Axis;
 use crate::widget::Widget;
-use crate::context::{BuildCx, CursorCx, LayoutCx};
+use crate::context::{BuildCx, CursorCx, LayoutCx, ViewId};
 
 /*
 #########################################################
@@ -82,6 +84,14 @@ impl AnyView {
     }
 }
 
+impl IntoView for AnyView {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
 impl Widget for AnyView {
     fn build(&self, cx: &mut BuildCx<'_>) -> bool {
         self.as_widget_ref().build(cx)
@@ -96,6 +106,76 @@ impl Widget for AnyView {
     }
 }
 
+/*
+#########################################################
+#
+# WidgetId
+#
+#########################################################
+*/
+
+/// Handle to the [`ViewId`] of a view, filled in when the view is built. The id is kept
+/// across rebuilds as long as the view stays at the same place in the tree
+/// Look up its rect with [`LayoutCx::bounds_of_widget`] or [`CursorCx::bounds_of_widget`]
+#[derive(Debug, Clone, Default)]
+pub struct WidgetId(Rc<Cell<Option<ViewId>>>);
+
+impl WidgetId {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// [`None`] until the view is built
+    pub fn get(&self) -> Option<ViewId> {
+        self.0.get()
+    }
+}
+
+pub trait IdentifyView: IntoView {
+    /// Record the id of the outermost element of this view into `id`
+    fn id(self, id: &WidgetId) -> Identified<Self::View> {
+        Identified {
+            view: self.into_view(),
+            id: id.clone(),
+        }
+    }
+}
+
+impl<IV: IntoView> IdentifyView for IV {}
+
+pub struct Identified<W> {
+    view: W,
+    id: WidgetId,
+}
+
+impl<W: Widget> Widget for Identified<W> {
+    fn debug_name(&self) -> &'static str {
+        self.view.debug_name()
+    }
+
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        let dirty = self.view.build(cx);
+        self.id.0.set(cx.get_id().copied());
+        dirty
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        self.view.layout(cx);
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        self.view.detect_hover(cx)
+    }
+}
+
+impl<W: Widget> IntoView for Identified<W> {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
 /*
 #########################################################
 #
@@ -158,10 +238,26 @@ macro_rules! view_tuple {
                 #[allow(non_snake_case)]
                 let ($($name,)*) = self;
 
-                let mut count = 0;
+                let mut count = 0usize;
 
                 for_each($($name,)* |_| count += 1);
 
+                if let Some(grid) = cx.rules.grid {
+                    let (bound, rules) = (cx.bound, cx.rules);
+                    let mut path_id = cx.pop();
+                    let mut index = 0;
+
+                    for_each($($name,)* |w| {
+                        let mut cx = LayoutCx::derive(cx, rules, grid.cell(bound, index, count));
+                        cx.layout_child(path_id, w);
+                        path_id += 1;
+                        index += 1;
+                    });
+
+                    cx.push(path_id);
+                    return;
+                }
+
                 let spacing = cx.rules.spacing.0 as f32;
 
                 let bound = match cx.rules.axis {
@@ -179,41 +275,33 @@ macro_rules! view_tuple {
 
                 let mut path_id = cx.pop();
 
-                for_each($($name,)* |w| cx.with_id(path_id, |cx| {
-                    w.layout(cx);
+                for_each($($name,)* |w| {
+                    cx.layout_child(path_id, w);
                     path_id += 1;
-                }));
+                });
 
                 cx.push(path_id);
             }
 
             fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
                 #[allow(non_snake_case, clippy::too_many_arguments)]
-                fn any<$($name: Widget),*>(
-                    $($name: &$name,)*
-                    mut f: impl FnMut(&dyn Widget) -> bool
-                ) -> bool {
-                    ($(
-                        if f($name) {
-                            return true;
-                        }
-                    ,)*);
-
-                    false
+                fn collect<'w, $($name: Widget),*>($($name: &'w $name,)*) -> Vec<&'w dyn Widget> {
+                    vec![$($name as &dyn Widget,)*]
                 }
 
-                let mut path_id = cx.pop();
-
                 #[allow(non_snake_case)]
                 let ($($name,)*) = self;
 
-                let res = any($($name,)* |w| cx.with_id(path_id, |cx| {
-                    let res = w.detect_hover(cx);
-                    path_id += 1;
-                    res
-                }));
+                let children = collect($($name,)*);
+                let path_id = cx.pop();
 
-                cx.push(path_id);
+                let res = cx.hit_order(path_id, children.len())
+                    .into_iter()
+                    .any(|offset| cx.with_id(path_id + offset, |cx| {
+                        children[offset as usize].detect_hover(cx)
+                    }));
+
+                cx.push(path_id + children.len() as u32);
 
                 res
             }
@@ -288,6 +376,57 @@ mod view_test {
         assert_ne!(s_tuple.type_id(), s_arr.type_id());
     }
 
+    /// Records the bounds of the identified view while laid out
+    struct Overlay(WidgetId, Rc<Cell<Option<Rect>>>);
+
+    impl Widget for Overlay {
+        fn build(&self, _cx: &mut BuildCx<'_>) -> bool { false }
+
+        fn layout(&self, cx: &mut LayoutCx<'_>) {
+            self.1.set(cx.bounds_of_widget(&self.0));
+        }
+
+        fn detect_hover(&self, _cx: &mut CursorCx<'_>) -> bool { false }
+    }
+
+    impl IntoView for Overlay {
+        type View = Self;
+
+        fn into_view(self) -> Self::View {
+            self
+        }
+    }
+
+    #[test]
+    fn widget_id_is_stable() {
+        use crate::context::Context;
+        use aplite_types::Length;
+
+        let (first, second) = (WidgetId::new(), WidgetId::new());
+        let anchored = Rc::new(Cell::new(None));
+        let view = || vstack((
+            button("", || {})
+                .style(|elem, _| elem.height = Length::Fixed(40.))
+                .id(&first),
+            circle().id(&second),
+            Overlay(first.clone(), anchored.clone()),
+        )).into_view();
+
+        let mut cx = Context::new((500, 500).into());
+        assert_eq!(first.get(), None);
+
+        cx.build(&view());
+        cx.layout(&view());
+        let ids = (first.get().unwrap(), second.get().unwrap());
+        assert_ne!(ids.0, ids.1);
+        assert_eq!(anchored.get().map(|rect| rect.height), Some(40.));
+
+        // redraw phase, then the rebuilt view maps onto the same ids
+        cx.build(&view());
+        cx.build(&view());
+        assert_eq!((first.get().unwrap(), second.get().unwrap()), ids);
+    }
+
     #[test]
     fn either_test() {
         let (when, set_when) = Signal::split(false);
@@ -1,26 +1,40 @@
 use aplite_reactive::*;
 use aplite_renderer::Scene;
 use aplite_types::{
-    Color, Length, Matrix3x2, PaintRef, Rect, theme
+    Color, Length, PaintRef, Rect, theme
 };
 
-use crate::{layout::Axis, state::BorderWidth};
+use crate::{layout::Axis, state::{BorderWidth, Rotation}};
+use crate::theme::Theme;
 use crate::view::IntoView;
 use crate::context::{BuildCx, LayoutCx, CursorCx};
 
 mod button;
 mod image;
+mod label;
 mod stack;
+mod grid;
 mod either;
+mod dynamic;
+mod for_each;
+mod show;
 mod text;
 mod iterables;
 mod view_fn;
 
+#[cfg(test)]
+pub(crate) mod test_util;
+
 pub use {
     button::*,
     image::*,
+    label::*,
     stack::*,
+    grid::*,
     either::*,
+    dynamic::*,
+    for_each::*,
+    show::*,
     text::*,
     view_fn::*,
 };
@@ -33,7 +47,9 @@ pub use {
 #########################################################
 */
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum InteractionState {
+    #[default]
     Idle,
     Hovered,
     Focused,
@@ -65,6 +81,11 @@ pub trait Renderable: std::fmt::Debug + 'static {
         std::any::TypeId::of::<Self>()
     }
 
+    /// Paint & hit-test order among siblings, higher is on top. It adds up with the ancestors' z index
+    fn z_index(&self) -> i32 {
+        0
+    }
+
     fn equal(&self, other: &dyn Renderable) -> bool;
 }
 
@@ -112,7 +133,7 @@ impl IntoView for CircleWidget {
 
 impl Widget for CircleWidget {
     fn build(&self, cx: &mut BuildCx<'_>) -> bool {
-        let mut state = CircleElement::new();
+        let mut state = CircleElement::new(cx.theme());
 
         if let Some(style_fn) = self.style_fn.as_ref() {
             style_fn(&mut state);
@@ -161,6 +182,7 @@ pub struct CircleElement {
     pub background: Color,
     pub border_color: Color,
     pub border_width: BorderWidth,
+    pub rotation: Rotation,
 }
 
 impl std::fmt::Debug for CircleElement {
@@ -171,21 +193,35 @@ impl std::fmt::Debug for CircleElement {
 }
 
 impl CircleElement {
-    fn new() -> Self {
-        Self {
+    fn new(theme: Option<Theme>) -> Self {
+        let elem = Self {
             radius: Length::Grow,
             background: theme::gruvbox_dark::RED_0,
             border_color: theme::gruvbox_dark::RED_1,
             border_width: BorderWidth(10.),
+            rotation: Rotation(0.),
+        };
+
+        match theme {
+            Some(theme) => Self {
+                background: theme.accent,
+                border_color: theme.border,
+                ..elem
+            },
+            None => elem,
         }
     }
+
+    pub fn rotate(&mut self, radians: f32) {
+        self.rotation.0 += radians;
+    }
 }
 
 impl Renderable for CircleElement {
     fn render(&self, rect: &Rect, scene: &mut Scene) {
         scene.draw_circle(
             rect,
-            &Matrix3x2::identity(),
+            &self.rotation.transform(),
             &PaintRef::from(&self.background),
             &PaintRef::from(&self.border_color),
             &self.border_width.0
@@ -237,6 +273,16 @@ impl<IV: IntoView> IntoView for Option<IV> {
     }
 }
 
+// -- Result<IV, E>
+/// Renders the [`Ok`] value, an [`Err`] is dropped and renders nothing like [`None`]
+impl<IV: IntoView, E: 'static> IntoView for Result<IV, E> {
+    type View = Option<IV::View>;
+
+    fn into_view(self) -> Self::View {
+        self.ok().into_view()
+    }
+}
+
 /*
 #########################################################
 #
@@ -302,3 +348,33 @@ macro_rules! impl_reactive_nodes {
 impl_reactive_nodes!(SignalRead<IV> where IV: Widget);
 impl_reactive_nodes!(Signal<IV> where IV: Widget);
 impl_reactive_nodes!(Memo<IV> where IV: Widget);
+
+#[cfg(test)]
+mod widget_test {
+    use super::*;
+    use crate::context::Context;
+
+    fn build(cx: &mut Context, view: &impl Widget) {
+        cx.build(view);
+        // redraw phase
+        cx.build(view);
+    }
+
+    #[test]
+    fn optional_views() {
+        let mut cx = Context::new((500, 500).into());
+
+        build(&mut cx, &None::<CircleWidget>.into_view());
+        assert!(cx.order.is_empty());
+
+        // stack, circle, button, its content
+        build(&mut cx, &Some(hstack((circle(), button("", || {})))).into_view());
+        assert_eq!(cx.order.len(), 4);
+
+        build(&mut cx, &Err::<CircleWidget, _>("nope").into_view());
+        assert!(cx.order.is_empty());
+
+        build(&mut cx, &Ok::<_, ()>(circle()).into_view());
+        assert_eq!(cx.order.len(), 1);
+    }
+}
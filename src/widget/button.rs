@@ -1,11 +1,13 @@
 use aplite_renderer::Scene;
-use aplite_types::{Length, Matrix3x2, PaintRef, Rect};
+use aplite_types::{Length, PaintRef, Rect};
 use aplite_types::{CornerRadius, Color};
 use aplite_types::theme::gruvbox_dark as theme;
 
+use crate::callback::{EventFn, WidgetEvent};
 use crate::context::{BuildCx, LayoutCx, CursorCx};
-use crate::layout::{AlignH, AlignV, Axis, LayoutRules, Padding, Spacing};
-use crate::state::BorderWidth;
+use crate::layout::{AlignH, AlignV, Axis, LayoutRules, Margin, Padding, Spacing};
+use crate::state::{BorderWidth, FontSize, Rotation};
+use crate::theme::Theme;
 use crate::view::IntoView;
 use crate::widget::{Renderable, Widget, InteractionState};
 
@@ -20,6 +22,11 @@ where
 pub struct Button<IV: IntoView, F> {
     content: IV::View,
     callback: F,
+    label: Option<String>,
+    on_hover: Option<Box<dyn Fn()>>,
+    on_hover_out: Option<Box<dyn Fn()>>,
+    on_secondary_click: Option<Box<dyn Fn()>>,
+    on_event: Option<EventFn<ButtonElement>>,
 
     #[allow(clippy::type_complexity)]
     style_fn: Option<Box<dyn Fn(&mut ButtonElement, InteractionState)>>,
@@ -30,10 +37,56 @@ impl<IV: IntoView, F: Fn() + 'static> Button<IV, F> {
         Self {
             content: content.into_view(),
             callback,
+            label: None,
+            on_hover: None,
+            on_hover_out: None,
+            on_secondary_click: None,
+            on_event: None,
             style_fn: None,
         }
     }
 
+    /// Draw a text label centered in the button, on top of the content
+    pub fn text(self, text: &str) -> Self {
+        Self {
+            label: Some(text.to_string()),
+            ..self
+        }
+    }
+
+    /// Called once when the cursor enters the button
+    pub fn on_hover(self, f: impl Fn() + 'static) -> Self {
+        Self {
+            on_hover: Some(Box::new(f)),
+            ..self
+        }
+    }
+
+    /// Called once when the cursor leaves the button after hovering it
+    pub fn on_hover_out(self, f: impl Fn() + 'static) -> Self {
+        Self {
+            on_hover_out: Some(Box::new(f)),
+            ..self
+        }
+    }
+
+    /// Called when the button is pressed and released with the right mouse button, e.g. to open a context menu
+    pub fn on_secondary_click(self, f: impl Fn() + 'static) -> Self {
+        Self {
+            on_secondary_click: Some(Box::new(f)),
+            ..self
+        }
+    }
+
+    /// Receive every pointer and keyboard event while hovered. The element is rebuilt from the style
+    /// function on every build, only [`ButtonElement::interaction`] set by the handler persists
+    pub fn on_event(self, f: impl FnMut(&mut ButtonElement, WidgetEvent) + 'static) -> Self {
+        Self {
+            on_event: Some(EventFn::new(f)),
+            ..self
+        }
+    }
+
     pub fn style(self, style_fn: impl Fn(&mut ButtonElement, InteractionState) + 'static) -> Self {
         Self {
             style_fn: Some(Box::new(style_fn)),
@@ -44,13 +97,18 @@ impl<IV: IntoView, F: Fn() + 'static> Button<IV, F> {
 
 impl<IV: IntoView, F: Fn() + 'static> Widget for Button<IV, F> {
     fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        let interaction = cx.get_element::<ButtonElement>()
+            .map(|elem| elem.interaction)
+            .unwrap_or_default();
+
         let mut elem = ButtonElement {
-            z_index: cx.get_z_index(),
-            ..ButtonElement::new()
+            label: self.label.clone().unwrap_or_default(),
+            interaction,
+            ..ButtonElement::new(cx.theme())
         };
 
         if let Some(style_fn) = self.style_fn.as_ref() {
-            style_fn(&mut elem, InteractionState::Idle);
+            style_fn(&mut elem, interaction);
         }
 
         let dirty = cx.add_or_update_element(elem);
@@ -61,16 +119,17 @@ impl<IV: IntoView, F: Fn() + 'static> Widget for Button<IV, F> {
 
     fn layout(&self, cx: &mut LayoutCx<'_>) {
         let elem = cx.get_element::<ButtonElement>().unwrap();
+        let margin = elem.margin;
         let bound = cx.bound;
 
         let width = match elem.width {
-            Length::Grow => bound.width,
+            Length::Grow => bound.width - margin.horizontal() as f32,
             Length::Fixed(val) => val,
             Length::FitContent => 0.,
         };
 
         let height = match elem.height {
-            Length::Grow => bound.height,
+            Length::Grow => bound.height - margin.vertical() as f32,
             Length::Fixed(val) => val,
             Length::FitContent => 0.,
         };
@@ -81,16 +140,22 @@ impl<IV: IntoView, F: Fn() + 'static> Widget for Button<IV, F> {
             align_h: elem.align_h,
             align_v: elem.align_v,
             spacing: elem.spacing,
+            grid: None,
         };
 
-        let layout_node = Rect::new(bound.x, bound.y, width, height);
+        let layout_node = Rect::new(
+            bound.x + margin.left as f32,
+            bound.y + margin.top as f32,
+            width,
+            height,
+        );
 
         match cx.rules.axis {
             Axis::Horizontal => {
-                cx.bound.x += width + cx.rules.spacing.0 as f32;
+                cx.bound.x += width + margin.horizontal() as f32 + cx.rules.spacing.0 as f32;
             },
             Axis::Vertical =>  {
-                cx.bound.y += height + cx.rules.spacing.0 as f32;
+                cx.bound.y += height + margin.vertical() as f32 + cx.rules.spacing.0 as f32;
             },
         }
 
@@ -120,7 +185,7 @@ impl<IV: IntoView, F: Fn() + 'static> Widget for Button<IV, F> {
 
         let mut cx = LayoutCx::derive(cx, rules, bound);
 
-        cx.with_id(0, |cx| self.content.layout(cx));
+        cx.layout_child(0, &self.content);
     }
 
     fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
@@ -130,6 +195,13 @@ impl<IV: IntoView, F: Fn() + 'static> Widget for Button<IV, F> {
 
         if hovered {
             cx.set_id();
+            cx.set_callback_on_hover(self.on_hover.as_deref(), self.on_hover_out.as_deref());
+            if let Some(on_secondary_click) = self.on_secondary_click.as_deref() {
+                cx.set_callback_on_secondary_click(on_secondary_click);
+            }
+            if let Some(on_event) = self.on_event.as_ref() {
+                cx.set_event_handler(on_event);
+            }
             cx.set_callback_on_click(&self.callback);
             // cx.set_callback_on_click(|| {
             //     NonNull::from_ref(&self.callback as &dyn Fn())
@@ -155,6 +227,7 @@ pub struct ButtonElement {
     pub width: Length,
     pub height: Length,
     pub padding: Padding,
+    pub margin: Margin,
     pub spacing: Spacing,
     pub align_h: AlignH,
     pub align_v: AlignV,
@@ -162,8 +235,15 @@ pub struct ButtonElement {
     pub border_color: Color,
     pub border_width: BorderWidth,
     pub corner_radius: CornerRadius,
+    pub rotation: Rotation,
+    pub font_size: FontSize,
+    pub text_color: Color,
+    label: String,
     axis: Axis,
-    z_index: u32,
+    /// Paint & hit-test order among siblings, higher is on top
+    pub z_index: i32,
+    /// Kept across rebuilds and passed to the style function, set it from [`Button::on_event`]
+    pub interaction: InteractionState,
 }
 
 impl std::fmt::Debug for ButtonElement {
@@ -174,11 +254,12 @@ impl std::fmt::Debug for ButtonElement {
 }
 
 impl ButtonElement {
-    fn new() -> Self {
-        Self {
+    fn new(theme: Option<Theme>) -> Self {
+        let elem = Self {
             width: Length::Grow,
             height: Length::Grow,
             padding: Padding::splat(5),
+            margin: Margin::splat(0),
             spacing: Spacing(5),
             align_h: AlignH::Center,
             align_v: AlignV::Middle,
@@ -187,21 +268,62 @@ impl ButtonElement {
             border_color: theme::GREEN_1,
             border_width: BorderWidth(5.),
             corner_radius: CornerRadius::splat(5),
+            rotation: Rotation(0.),
+            font_size: FontSize(16.),
+            text_color: theme::FG_0,
+            label: String::new(),
             z_index: 0,
+            interaction: InteractionState::Idle,
+        };
+
+        match theme {
+            Some(theme) => Self {
+                background: theme.accent,
+                border_color: theme.border,
+                corner_radius: theme.corner_radius,
+                text_color: theme.foreground,
+                ..elem
+            },
+            None => elem,
         }
     }
+
+    pub fn rotate(&mut self, radians: f32) {
+        self.rotation.0 += radians;
+    }
 }
 
 impl Renderable for ButtonElement {
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
     fn render(&self, rect: &Rect, scene: &mut Scene) {
         scene.draw_rounded_rect(
             rect,
-            &Matrix3x2::identity(),
+            &self.rotation.transform(),
             &PaintRef::from(&self.background),
             &PaintRef::from(&self.border_color),
             &self.border_width.0,
             &self.corner_radius,
         );
+
+        if !self.label.is_empty() {
+            let size = scene.measure_text(&self.label, self.font_size.0);
+            let label_rect = Rect::new(
+                rect.x + (rect.width - size.width) / 2.,
+                rect.y + (rect.height - size.height) / 2.,
+                size.width,
+                size.height,
+            );
+
+            scene.draw_text(
+                &self.label,
+                self.font_size.0,
+                &label_rect,
+                &self.text_color,
+            );
+        }
     }
 
     fn equal(&self, other: &dyn Renderable) -> bool {
@@ -215,3 +337,317 @@ impl Renderable for ButtonElement {
         false
     }
 }
+
+#[cfg(test)]
+mod button_test {
+    use aplite_types::rgb;
+    use super::*;
+    use crate::app::AppConfig;
+    use aplite_reactive::Set;
+
+    fn button_element(elements: &[Box<dyn Renderable>]) -> &ButtonElement {
+        let elem = elements[0].as_ref();
+        assert_eq!(elem.type_id(), std::any::TypeId::of::<ButtonElement>());
+        unsafe { &*(elem as *const dyn Renderable as *const ButtonElement) }
+    }
+
+    #[test]
+    fn default_fill_from_theme() {
+        let theme = Theme {
+            accent: rgb(0x123456),
+            ..Theme::default()
+        };
+
+        let mut cx = AppConfig::default().theme(theme).context();
+        cx.build(&button("", || {}).into_view());
+        assert_eq!(button_element(&cx.elements).background, rgb(0x123456));
+
+        let mut cx = AppConfig::default().theme(theme).context();
+        let styled = button("", || {}).style(|elem, _| elem.background = rgb(0xabcdef));
+        cx.build(&styled.into_view());
+        assert_eq!(button_element(&cx.elements).background, rgb(0xabcdef));
+    }
+
+    #[test]
+    fn toggle_theme_signal() {
+        let mut cx = AppConfig::default().theme(Theme::default()).context();
+        let view = button("", || {}).into_view();
+
+        assert!(cx.build(&view));
+        assert_eq!(button_element(&cx.elements).background, Theme::default().accent);
+        // redraw phase
+        cx.build(&view);
+
+        cx.set_theme(Theme::LIGHT);
+        assert!(cx.build(&view));
+        assert_eq!(button_element(&cx.elements).background, Theme::LIGHT.accent);
+        cx.build(&view);
+
+        cx.theme().unwrap().set(Theme::default());
+        assert!(cx.build(&view));
+        assert_eq!(button_element(&cx.elements).background, Theme::default().accent);
+    }
+
+    #[test]
+    fn text_label() {
+        let mut cx = AppConfig::default().theme(Theme::default()).context();
+        cx.build(&button("", || {}).text("click me").into_view());
+
+        let elem = button_element(&cx.elements);
+        assert_eq!(elem.label, "click me");
+        assert_eq!(elem.text_color, Theme::default().foreground);
+    }
+
+    #[test]
+    fn hover_enter_then_leave() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let enter = events.clone();
+        let leave = events.clone();
+
+        let view = button("", || {})
+            .on_hover(move || enter.borrow_mut().push("enter"))
+            .on_hover_out(move || leave.borrow_mut().push("leave"))
+            .into_view();
+
+        let mut cx = AppConfig::default().inner_size((100, 100).into()).context();
+        cx.build(&view);
+        cx.layout(&view);
+
+        cx.handle_mouse_move((50., 50.), &view);
+        cx.handle_mouse_move((60., 60.), &view);
+        assert_eq!(*events.borrow(), ["enter"]);
+
+        cx.handle_mouse_move((200., 200.), &view);
+        assert_eq!(*events.borrow(), ["enter", "leave"]);
+
+        cx.handle_mouse_move((300., 300.), &view);
+        assert_eq!(*events.borrow(), ["enter", "leave"]);
+    }
+
+    #[test]
+    fn event_sequence() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use winit::keyboard::{Key, NamedKey};
+        use crate::cursor::{MouseAction, MouseButton};
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let received = events.clone();
+
+        let view = button("", || {})
+            .on_event(move |elem, event| {
+                if event == WidgetEvent::PointerEnter {
+                    elem.interaction = InteractionState::Hovered;
+                }
+                received.borrow_mut().push(event);
+            })
+            .style(|elem, state| if state == InteractionState::Hovered {
+                elem.background = rgb(0xabcdef);
+            })
+            .into_view();
+
+        let mut cx = AppConfig::default().inner_size((100, 100).into()).context();
+        cx.build(&view);
+        cx.layout(&view);
+
+        cx.handle_mouse_move((50., 50.), &view);
+        cx.handle_click(MouseAction::Pressed, MouseButton::Left);
+        cx.handle_click(MouseAction::Released, MouseButton::Left);
+        cx.handle_event(WidgetEvent::Scroll((0., -20.).into()));
+        cx.handle_event(WidgetEvent::KeyDown(Key::Named(NamedKey::Enter)));
+        cx.handle_mouse_move((200., 200.), &view);
+
+        assert_eq!(*events.borrow(), [
+            WidgetEvent::PointerEnter,
+            WidgetEvent::PointerMove((50., 50.).into()),
+            WidgetEvent::PointerDown(MouseButton::Left),
+            WidgetEvent::PointerUp(MouseButton::Left),
+            WidgetEvent::Click((50., 50.).into()),
+            WidgetEvent::Scroll((0., -20.).into()),
+            WidgetEvent::KeyDown(Key::Named(NamedKey::Enter)),
+            WidgetEvent::PointerLeave,
+        ]);
+
+        // the interaction state set by the handler survives a rebuild, and restyles the element
+        cx.build(&view);
+        cx.build(&view);
+        assert_eq!(button_element(&cx.elements).interaction, InteractionState::Hovered);
+        assert_eq!(button_element(&cx.elements).background, rgb(0xabcdef));
+    }
+
+    #[test]
+    fn rebuild_with_event_handler() {
+        let mut cx = AppConfig::default().theme(Theme::default()).context();
+        let view = button("", || {})
+            .text("label")
+            .on_event(|_, _| {})
+            .into_view();
+
+        assert!(cx.build(&view));
+        assert_eq!(button_element(&cx.elements).label, "label");
+        // redraw phase
+        cx.build(&view);
+
+        cx.set_theme(Theme::LIGHT);
+        assert!(cx.build(&view));
+        assert_eq!(button_element(&cx.elements).background, Theme::LIGHT.accent);
+        assert_eq!(button_element(&cx.elements).label, "label");
+    }
+
+    #[test]
+    fn pointer_relative_to_widget() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use aplite_types::Vec2f;
+        use crate::cursor::{MouseAction, MouseButton};
+        use crate::widget::vstack;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let received = events.clone();
+
+        let view = vstack(
+            button("", || {})
+                .on_event(move |_, event| {
+                    if matches!(event, WidgetEvent::PointerMove(_) | WidgetEvent::Drag(_)) {
+                        received.borrow_mut().push(event);
+                    }
+                })
+        )
+        .style(|elem| elem.padding = Padding::new(20, 0, 30, 0))
+        .into_view();
+
+        let mut cx = AppConfig::default().inner_size((200, 200).into()).context();
+        cx.build(&view);
+        cx.layout(&view);
+
+        cx.handle_mouse_move((40., 25.), &view);
+        cx.handle_click(MouseAction::Pressed, MouseButton::Left);
+        // dragged outside of the button
+        cx.handle_mouse_move((10., 5.), &view);
+        cx.handle_click(MouseAction::Released, MouseButton::Left);
+        cx.handle_mouse_move((40., 25.), &view);
+
+        assert_eq!(*events.borrow(), [
+            WidgetEvent::PointerMove(Vec2f::new(10., 5.)),
+            WidgetEvent::Drag(Vec2f::new(-20., -15.)),
+            WidgetEvent::PointerMove(Vec2f::new(10., 5.)),
+        ]);
+    }
+
+    #[test]
+    fn click_position() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use aplite_types::Vec2f;
+        use crate::cursor::{MouseAction, MouseButton};
+        use crate::widget::vstack;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let received = events.clone();
+
+        let view = vstack(
+            button("", || {})
+                .on_event(move |_, event| {
+                    if matches!(event, WidgetEvent::Click(_)) {
+                        received.borrow_mut().push(event);
+                    }
+                })
+        )
+        .style(|elem| elem.padding = Padding::new(20, 0, 30, 0))
+        .into_view();
+
+        let mut cx = AppConfig::default().inner_size((200, 200).into()).context();
+        cx.build(&view);
+        cx.layout(&view);
+
+        cx.handle_mouse_move((40., 25.), &view);
+        cx.handle_click(MouseAction::Pressed, MouseButton::Left);
+        cx.handle_click(MouseAction::Released, MouseButton::Left);
+
+        // released outside of the button
+        cx.handle_click(MouseAction::Pressed, MouseButton::Left);
+        cx.handle_mouse_move((10., 5.), &view);
+        cx.handle_click(MouseAction::Released, MouseButton::Left);
+
+        assert_eq!(*events.borrow(), [WidgetEvent::Click(Vec2f::new(10., 5.))]);
+    }
+
+    #[test]
+    fn secondary_click() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use crate::cursor::{MouseAction, MouseButton};
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let received = events.clone();
+        let clicked = Rc::new(RefCell::new(0));
+        let secondary = clicked.clone();
+
+        let view = button("", || {})
+            .on_secondary_click(move || *secondary.borrow_mut() += 1)
+            .on_event(move |_, event| {
+                if matches!(event, WidgetEvent::PointerDown(_) | WidgetEvent::PointerUp(_)) {
+                    received.borrow_mut().push(event);
+                }
+            })
+            .into_view();
+
+        let mut cx = AppConfig::default().inner_size((100, 100).into()).context();
+        cx.build(&view);
+        cx.layout(&view);
+
+        cx.handle_mouse_move((50., 50.), &view);
+        cx.handle_click(MouseAction::Pressed, MouseButton::Right);
+        cx.handle_click(MouseAction::Released, MouseButton::Right);
+        assert_eq!(*clicked.borrow(), 1);
+
+        cx.handle_click(MouseAction::Pressed, MouseButton::Left);
+        cx.handle_click(MouseAction::Released, MouseButton::Left);
+        assert_eq!(*clicked.borrow(), 1);
+
+        // released outside of the button
+        cx.handle_click(MouseAction::Pressed, MouseButton::Right);
+        cx.handle_mouse_move((200., 200.), &view);
+        cx.handle_click(MouseAction::Released, MouseButton::Right);
+        assert_eq!(*clicked.borrow(), 1);
+
+        assert_eq!(events.borrow()[..2], [
+            WidgetEvent::PointerDown(MouseButton::Right),
+            WidgetEvent::PointerUp(MouseButton::Right),
+        ]);
+    }
+
+    #[test]
+    fn margins_add_up_between_siblings() {
+        use crate::context::Context;
+        use crate::widget::vstack;
+
+        let sized = |margin: Margin| {
+            button("", || {}).style(move |elem, _| {
+                elem.width = Length::Fixed(100.);
+                elem.height = Length::Fixed(40.);
+                elem.margin = margin;
+            })
+        };
+
+        let view = vstack((
+            sized(Margin::new(0, 8, 4, 0)),
+            sized(Margin::new(12, 0, 0, 0)),
+        ));
+
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        // stack, first button, its content, second button, its content
+        let first = cx.bounds_of(cx.order[1]).unwrap();
+        let second = cx.bounds_of(cx.order[3]).unwrap();
+
+        assert_eq!(first, Rect::new(4., 0., 100., 40.));
+        assert_eq!(second.y - first.max_y(), 8. + 12.);
+        assert_eq!(second.x, 0.);
+    }
+}
@@ -0,0 +1,196 @@
+use std::cell::RefCell;
+
+use aplite_reactive::{Get, Scope};
+
+use crate::context::{BuildCx, LayoutCx, CursorCx};
+use crate::view::IntoView;
+use crate::widget::Widget;
+
+/// Swap the content whenever the value of `signal` changes. The previous content is dropped along with
+/// its reactive scope, running its cleanups, and a fresh subtree is built in its place
+pub fn dynamic<S, T, F, IV>(signal: S, view_fn: F) -> Dynamic<S, T, F, IV::View>
+where
+    S: Get<Value = T> + 'static,
+    T: PartialEq + 'static,
+    F: Fn(T) -> IV + 'static,
+    IV: IntoView,
+{
+    Dynamic {
+        signal,
+        view_fn,
+        content: RefCell::new(None),
+    }
+}
+
+pub struct Dynamic<S, T, F, V> {
+    signal: S,
+    view_fn: F,
+    content: RefCell<Option<Content<T, V>>>,
+}
+
+struct Content<T, V> {
+    value: T,
+    scope: Scope,
+    view: V,
+    /// Part of the view path, so a new subtree never takes over the nodes of the old one
+    generation: u32,
+}
+
+impl<S, T, F, IV> Dynamic<S, T, F, IV::View>
+where
+    S: Get<Value = T> + 'static,
+    T: PartialEq + 'static,
+    F: Fn(T) -> IV + 'static,
+    IV: IntoView,
+{
+    fn update(&self) {
+        let value = self.signal.get_untracked();
+        let mut content = self.content.borrow_mut();
+
+        if content.as_ref().is_some_and(|content| content.value == value) {
+            return;
+        }
+
+        let generation = match content.take() {
+            Some(prev) => {
+                prev.scope.cleanup();
+                prev.generation.wrapping_add(1)
+            },
+            None => 0,
+        };
+
+        let scope = Scope::new();
+        let view = scope.with(|| (self.view_fn)(self.signal.get_untracked()).into_view());
+
+        *content = Some(Content {
+            value,
+            scope,
+            view,
+            generation,
+        });
+    }
+}
+
+/// Dropping the widget runs the cleanups of the current content
+impl<S, T, F, V> Drop for Dynamic<S, T, F, V> {
+    fn drop(&mut self) {
+        if let Some(content) = self.content.get_mut().take() {
+            content.scope.cleanup();
+        }
+    }
+}
+
+impl<S, T, F, IV> Widget for Dynamic<S, T, F, IV::View>
+where
+    S: Get<Value = T> + 'static,
+    T: PartialEq + 'static,
+    F: Fn(T) -> IV + 'static,
+    IV: IntoView,
+{
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        self.update();
+
+        let content = self.content.borrow();
+        let content = content.as_ref().unwrap();
+        cx.with_id(content.generation, |cx| content.view.build(cx))
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        if let Some(content) = self.content.borrow().as_ref() {
+            cx.layout_only_child(content.generation, &content.view);
+        }
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        self.content
+            .borrow()
+            .as_ref()
+            .is_some_and(|content| cx.with_id(content.generation, |cx| content.view.detect_hover(cx)))
+    }
+}
+
+impl<S, T, F, IV> IntoView for Dynamic<S, T, F, IV::View>
+where
+    S: Get<Value = T> + 'static,
+    T: PartialEq + 'static,
+    F: Fn(T) -> IV + 'static,
+    IV: IntoView,
+{
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+#[cfg(test)]
+mod dynamic_test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use aplite_reactive::{Set, Signal, on_cleanup};
+    use super::*;
+    use crate::context::{Context, ViewId};
+    use crate::widget::test_util::{Probe, label_of};
+
+    fn rendered(cx: &mut Context, view: &impl Widget) -> Vec<ViewId> {
+        cx.build(view);
+        // redraw phase
+        cx.build(view);
+        cx.order.clone()
+    }
+
+    #[test]
+    fn swap_content() {
+        let tab = Signal::new("first");
+        let cleanups = Rc::new(Cell::new(0));
+
+        let counter = cleanups.clone();
+        let view = dynamic(tab, move |name| {
+            let counter = counter.clone();
+            on_cleanup(move || counter.set(counter.get() + 1));
+            Probe::new(name)
+        });
+        let mut cx = Context::new((500, 500).into());
+
+        let first = rendered(&mut cx, &view);
+        assert_eq!(first.len(), 1);
+        assert_eq!(label_of(cx.elements[first[0].0 as usize].as_ref()), Some("first"));
+        assert_eq!(cleanups.get(), 0);
+
+        tab.set("second");
+        let second = rendered(&mut cx, &view);
+        assert_eq!(second.len(), 1);
+        assert_eq!(label_of(cx.elements[second[0].0 as usize].as_ref()), Some("second"));
+        assert_eq!(cleanups.get(), 1);
+
+        // same value, content is kept
+        tab.set("second");
+        rendered(&mut cx, &view);
+        assert_eq!(cleanups.get(), 1);
+
+        tab.set("first");
+        let third = rendered(&mut cx, &view);
+        assert_eq!(label_of(cx.elements[third[0].0 as usize].as_ref()), Some("first"));
+        assert_eq!(cleanups.get(), 2);
+    }
+
+    #[test]
+    fn drop_runs_cleanup() {
+        let tab = Signal::new("first");
+        let cleanups = Rc::new(Cell::new(0));
+
+        let counter = cleanups.clone();
+        let view = dynamic(tab, move |name| {
+            let counter = counter.clone();
+            on_cleanup(move || counter.set(counter.get() + 1));
+            Probe::new(name)
+        });
+        let mut cx = Context::new((500, 500).into());
+
+        rendered(&mut cx, &view);
+        assert_eq!(cleanups.get(), 0);
+
+        drop(view);
+        assert_eq!(cleanups.get(), 1);
+    }
+}
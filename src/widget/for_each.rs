@@ -0,0 +1,275 @@
+use std::cell::RefCell;
+use std::hash::Hash;
+
+use aplite_reactive::{Get, Scope, Signal};
+use aplite_types::Rect;
+use rustc_hash::FxHashMap;
+
+use crate::context::{BuildCx, LayoutCx, CursorCx};
+use crate::layout::Axis;
+use crate::view::IntoView;
+use crate::widget::Widget;
+
+/// Keyed list of children. Each child is identified by its key instead of its position,
+/// so reordering or inserting items keeps the existing children and their ids.
+/// Repeated keys are told apart by the order they appear in
+pub fn for_each<T, K, KF, CF, IV>(each: Signal<Vec<T>>, key: KF, children: CF) -> For<T, K, KF, CF, IV::View>
+where
+    T: Clone + 'static,
+    K: Hash + Eq + Clone + 'static,
+    KF: Fn(&T) -> K + 'static,
+    CF: Fn(&T) -> IV + 'static,
+    IV: IntoView,
+{
+    For {
+        each,
+        key,
+        children,
+        rows: RefCell::new(Vec::new()),
+    }
+}
+
+pub struct For<T, K, KF, CF, V> {
+    each: Signal<Vec<T>>,
+    key: KF,
+    children: CF,
+    /// The children in item order, each built once for its key & occurrence
+    rows: RefCell<Vec<Row<K, V>>>,
+}
+
+struct Row<K, V> {
+    key: (K, usize),
+    scope: Scope,
+    view: V,
+    /// Part of the view path, kept while the key stays in the list
+    slot: u32,
+}
+
+impl<T, K, KF, CF, IV> For<T, K, KF, CF, IV::View>
+where
+    T: Clone + 'static,
+    K: Hash + Eq + Clone + 'static,
+    KF: Fn(&T) -> K + 'static,
+    CF: Fn(&T) -> IV + 'static,
+    IV: IntoView,
+{
+    /// Keep the children of the keys still in the list, and build the new keys into a slot no other key has.
+    /// Children of removed keys are dropped along with their reactive scope
+    fn update(&self) {
+        let items = self.each.get_untracked();
+        let mut rows = self.rows.borrow_mut();
+
+        let mut prev = rows
+            .drain(..)
+            .map(|row| (row.key.clone(), row))
+            .collect::<FxHashMap<_, _>>();
+        let mut next = prev.values().map(|row| row.slot).max().map_or(0, |max| max + 1);
+        let mut occurrences = FxHashMap::<K, usize>::default();
+
+        rows.extend(items.iter().map(|item| {
+            let key = (self.key)(item);
+            let occurrence = occurrences.entry(key.clone()).or_default();
+            let key = (key, *occurrence);
+            *occurrence += 1;
+
+            match prev.remove(&key) {
+                Some(row) => row,
+                None => {
+                    let scope = Scope::new();
+                    let view = scope.with(|| (self.children)(item).into_view());
+                    next += 1;
+                    Row { key, scope, view, slot: next - 1 }
+                },
+            }
+        }));
+
+        prev.into_values().for_each(|row| row.scope.cleanup());
+    }
+}
+
+/// Dropping the widget runs the cleanups of every child
+impl<T, K, KF, CF, V> Drop for For<T, K, KF, CF, V> {
+    fn drop(&mut self) {
+        self.rows.get_mut().drain(..).for_each(|row| row.scope.cleanup());
+    }
+}
+
+impl<T, K, KF, CF, IV> Widget for For<T, K, KF, CF, IV::View>
+where
+    T: Clone + 'static,
+    K: Hash + Eq + Clone + 'static,
+    KF: Fn(&T) -> K + 'static,
+    CF: Fn(&T) -> IV + 'static,
+    IV: IntoView,
+{
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        self.update();
+
+        self.rows
+            .borrow()
+            .iter()
+            .fold(false, |dirty, row| {
+                let child_dirty = cx.with_id(row.slot, |cx| row.view.build(cx));
+                dirty || child_dirty
+            })
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        let rows = self.rows.borrow();
+
+        if let Some(grid) = cx.rules.grid {
+            let (bound, rules) = (cx.bound, cx.rules);
+
+            rows.iter().enumerate().for_each(|(index, row)| {
+                let mut cx = LayoutCx::derive(cx, rules, grid.cell(bound, index, rows.len()));
+                cx.layout_child(row.slot, &row.view);
+            });
+
+            return;
+        }
+
+        let count = rows.len().max(1);
+
+        let bound = match cx.rules.axis {
+            Axis::Horizontal => {
+                let width = cx.bound.width / count as f32;
+                Rect::new(cx.bound.x, cx.bound.y, width, cx.bound.height)
+            },
+            Axis::Vertical => {
+                let height = cx.bound.height / count as f32;
+                Rect::new(cx.bound.x, cx.bound.y, cx.bound.width, height)
+            },
+        };
+
+        let mut cx = LayoutCx::derive(cx, cx.rules, bound);
+
+        rows.iter().for_each(|row| cx.layout_child(row.slot, &row.view));
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        self.rows
+            .borrow()
+            .iter()
+            .any(|row| cx.with_id(row.slot, |cx| row.view.detect_hover(cx)))
+    }
+}
+
+impl<T, K, KF, CF, IV> IntoView for For<T, K, KF, CF, IV::View>
+where
+    T: Clone + 'static,
+    K: Hash + Eq + Clone + 'static,
+    KF: Fn(&T) -> K + 'static,
+    CF: Fn(&T) -> IV + 'static,
+    IV: IntoView,
+{
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+#[cfg(test)]
+mod for_each_test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use aplite_reactive::{Set, on_cleanup};
+    use super::*;
+    use crate::context::{Context, ViewId};
+    use crate::widget::test_util::{Built, Probe};
+
+    fn build(cx: &mut Context, view: &impl Widget, built: &Built) -> Vec<(String, ViewId)> {
+        built.borrow_mut().clear();
+        cx.build(view);
+        // redraw phase
+        cx.build(view);
+        built.borrow().clone()
+    }
+
+    fn id_of(ids: &[(String, ViewId)], item: u32) -> ViewId {
+        ids.iter().find(|(n, _)| *n == item.to_string()).unwrap().1
+    }
+
+    #[test]
+    fn keyed_children_keep_their_ids() {
+        let built = Built::default();
+        let items = Signal::new(vec![1u32, 2, 3]);
+        let probes = built.clone();
+        let view = for_each(items, |n| *n, move |n| Probe::new(n).record(&probes));
+        let mut cx = Context::new((500, 500).into());
+
+        let first = build(&mut cx, &view, &built);
+        assert_eq!(first.len(), 3);
+
+        // reorder and insert
+        items.set(vec![3, 1, 4, 2]);
+        let second = build(&mut cx, &view, &built);
+        assert_eq!(second.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(), ["3", "1", "4", "2"]);
+
+        for n in [1, 2, 3] {
+            assert_eq!(id_of(&first, n), id_of(&second, n));
+        }
+        assert!(first.iter().all(|(_, id)| *id != id_of(&second, 4)));
+
+        // removed keys free their slot for the next insertion
+        items.set(vec![4, 2]);
+        let third = build(&mut cx, &view, &built);
+        assert_eq!(id_of(&third, 4), id_of(&second, 4));
+        assert_eq!(id_of(&third, 2), id_of(&second, 2));
+
+        items.set(vec![4, 2, 5]);
+        let fourth = build(&mut cx, &view, &built);
+        assert!(id_of(&fourth, 5) == id_of(&second, 1) || id_of(&fourth, 5) == id_of(&second, 3));
+        assert_eq!(cx.elements.len(), 4);
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let built = Built::default();
+        let items = Signal::new(vec![1u32, 1, 2]);
+        let probes = built.clone();
+        let view = for_each(items, |n| *n, move |n| Probe::new(n).record(&probes));
+        let mut cx = Context::new((500, 500).into());
+
+        let first = build(&mut cx, &view, &built);
+        assert_eq!(first.len(), 3);
+        assert_ne!(first[0].1, first[1].1);
+        assert_eq!(cx.elements.len(), 3);
+
+        // the first occurrence keeps its child
+        items.set(vec![2, 1]);
+        let second = build(&mut cx, &view, &built);
+        assert_eq!(second[0].1, first[2].1);
+        assert_eq!(second[1].1, first[0].1);
+    }
+
+    #[test]
+    fn children_built_once_per_key() {
+        let items = Signal::new(vec![1u32, 2]);
+        let (created, cleanups) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+
+        let (c, d) = (created.clone(), cleanups.clone());
+        let view = for_each(items, |n| *n, move |n| {
+            c.set(c.get() + 1);
+            let d = d.clone();
+            on_cleanup(move || d.set(d.get() + 1));
+            Probe::new(n)
+        });
+        let mut cx = Context::new((500, 500).into());
+
+        for _ in 0..2 {
+            cx.build(&view);
+            cx.layout(&view);
+            cx.handle_mouse_move((5., 5.), &view);
+        }
+        assert_eq!(created.get(), 2);
+
+        items.set(vec![2, 3]);
+        cx.build(&view);
+        cx.layout(&view);
+        assert_eq!((created.get(), cleanups.get()), (3, 1));
+
+        drop(view);
+        assert_eq!(cleanups.get(), 3);
+    }
+}
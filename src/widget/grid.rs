@@ -0,0 +1,287 @@
+use aplite_renderer::Scene;
+use aplite_types::{Color, CornerRadius, Length, PaintRef, Rect};
+use aplite_types::theme::basic;
+
+use crate::layout::{AlignH, AlignV, Axis, GridRules, LayoutRules, Margin, Padding, Spacing};
+use crate::context::{CursorCx, BuildCx, LayoutCx};
+use crate::state::{BorderWidth, Rotation};
+use crate::view::IntoView;
+use crate::widget::{Renderable, Widget};
+
+/// Arrange the children into `columns` columns, wrapping into a new row once a row is full
+pub fn grid<IV>(columns: usize, widget: IV) -> Grid<IV>
+where
+    IV: IntoView,
+{
+    Grid::new(columns, widget)
+}
+
+pub struct Grid<IV>
+where
+    IV: IntoView,
+{
+    pub(crate) content: IV::View,
+    columns: usize,
+
+    #[allow(clippy::type_complexity)]
+    style_fn: Option<Box<dyn Fn(&mut GridElement)>>,
+}
+
+impl<IV> Grid<IV>
+where
+    IV: IntoView,
+{
+    fn new(columns: usize, widget: IV) -> Self {
+        Self {
+            content: widget.into_view(),
+            columns,
+            style_fn: None,
+        }
+    }
+
+    pub fn style(self, style_fn: impl Fn(&mut GridElement) + 'static) -> Self {
+        Self {
+            style_fn: Some(Box::new(style_fn)),
+            ..self
+        }
+    }
+}
+
+impl<IV> Widget for Grid<IV>
+where
+    IV: IntoView,
+{
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        let mut elem = GridElement::new();
+
+        if let Some(style_fn) = self.style_fn.as_ref() {
+            style_fn(&mut elem);
+        }
+
+        let dirty = cx.add_or_update_element(elem);
+        let content_dirty = cx.with_id(0, |cx| self.content.build(cx));
+
+        dirty || content_dirty
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        let state = cx.get_element::<GridElement>().unwrap();
+        let margin = state.margin;
+
+        let width = match state.width {
+            Length::Grow => cx.bound.width - margin.horizontal() as f32,
+            Length::Fixed(val) => val,
+            Length::FitContent => 0.,
+        };
+
+        let height = match state.height {
+            Length::Grow => cx.bound.height - margin.vertical() as f32,
+            Length::Fixed(val) => val,
+            Length::FitContent => 0.,
+        };
+
+        let rules = LayoutRules {
+            padding: state.padding,
+            axis: Axis::Horizontal,
+            align_h: AlignH::Left,
+            align_v: AlignV::Top,
+            spacing: Spacing(0),
+            grid: Some(GridRules {
+                columns: self.columns,
+                row_gap: state.row_gap,
+                column_gap: state.column_gap,
+            }),
+        };
+
+        let layout_node = Rect::new(
+            cx.bound.x + margin.left as f32,
+            cx.bound.y + margin.top as f32,
+            width,
+            height,
+        );
+
+        match cx.rules.axis {
+            Axis::Horizontal => {
+                cx.bound.x += width + margin.horizontal() as f32 + cx.rules.spacing.0 as f32;
+            },
+            Axis::Vertical =>  {
+                cx.bound.y += height + margin.vertical() as f32 + cx.rules.spacing.0 as f32;
+            },
+        }
+
+        cx.set_node(layout_node);
+
+        let bound = Rect::new(
+            layout_node.x + rules.padding.left as f32,
+            layout_node.y + rules.padding.top as f32,
+            width - rules.padding.horizontal() as f32,
+            height - rules.padding.vertical() as f32
+        );
+
+        let mut cx = LayoutCx::derive(cx, rules, bound);
+
+        cx.layout_child(0, &self.content);
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        let hovered = cx.get_layout_node()
+            .map(|rect| rect.contains(cx.hover_pos()))
+            .unwrap_or_default();
+
+        if hovered && !cx.with_id(0, |cx| self.content.detect_hover(cx)) {
+            cx.set_id();
+        }
+
+        hovered
+    }
+}
+
+impl<IV: IntoView> IntoView for Grid<IV> {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+#[derive(PartialEq, Eq)]
+pub struct GridElement {
+    pub width: Length,
+    pub height: Length,
+    pub background: Color,
+    pub border_color: Color,
+    pub border_width: BorderWidth,
+    pub corner_radius: CornerRadius,
+    pub padding: Padding,
+    pub margin: Margin,
+    pub row_gap: Spacing,
+    pub column_gap: Spacing,
+    /// Paint & hit-test order among siblings, higher is on top
+    pub z_index: i32,
+}
+
+impl std::fmt::Debug for GridElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GridElement")
+            .finish_non_exhaustive()
+    }
+}
+
+impl GridElement {
+    fn new() -> Self {
+        Self {
+            width: Length::Grow,
+            height: Length::Grow,
+            background: basic::TRANSPARENT,
+            border_color: basic::TRANSPARENT,
+            border_width: BorderWidth(0.),
+            corner_radius: CornerRadius::splat(0),
+            padding: Padding::splat(0),
+            margin: Margin::splat(0),
+            row_gap: Spacing(0),
+            column_gap: Spacing(0),
+            z_index: 0,
+        }
+    }
+}
+
+impl Renderable for GridElement {
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    fn render(&self, rect: &Rect, scene: &mut Scene) {
+        scene.draw_rounded_rect(
+            rect,
+            &Rotation(0.).transform(),
+            &PaintRef::from(&self.background),
+            &PaintRef::from(&self.border_color),
+            &self.border_width.0,
+            &self.corner_radius,
+        );
+    }
+
+    fn equal(&self, other: &dyn Renderable) -> bool {
+        if other.type_id() == self.type_id() {
+            unsafe {
+                let ptr = other as *const dyn Renderable as *const Self;
+                (&*ptr).eq(self)
+            }
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod grid_test {
+    use crate::context::Context;
+    use crate::widget::button;
+    use super::*;
+
+    #[test]
+    fn wrap_into_rows() {
+        let view = grid(2, (
+            button("", || {}),
+            button("", || {}),
+            button("", || {}),
+            button("", || {}),
+            button("", || {}),
+        )).style(|elem| {
+            elem.row_gap = Spacing::new(10);
+            elem.column_gap = Spacing::new(20);
+        });
+
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        // grid, then every button followed by its content
+        let buttons = (0..5).map(|i| cx.order[1 + i * 2]).collect::<Vec<_>>();
+
+        // 3 rows: each cell is (500 - 20) / 2 wide and (500 - 2 * 10) / 3 high
+        let cell = |column: f32, row: f32| Rect::new(column * 260., row * 170., 240., 160.);
+
+        assert_eq!(cx.bounds_of(buttons[0]), Some(cell(0., 0.)));
+        assert_eq!(cx.bounds_of(buttons[1]), Some(cell(1., 0.)));
+        assert_eq!(cx.bounds_of(buttons[2]), Some(cell(0., 1.)));
+        assert_eq!(cx.bounds_of(buttons[3]), Some(cell(1., 1.)));
+        assert_eq!(cx.bounds_of(buttons[4]), Some(cell(0., 2.)));
+    }
+
+    #[test]
+    fn for_each_into_cells() {
+        use aplite_reactive::{Set, Signal};
+        use crate::widget::for_each;
+
+        let items = Signal::new(vec![1u32, 2, 3]);
+        let view = grid(2, for_each(items, |n| *n, |_| button("", || {})))
+            .style(|elem| {
+                elem.row_gap = Spacing::new(10);
+                elem.column_gap = Spacing::new(20);
+            });
+
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+        // redraw phase
+        cx.build(&view);
+
+        let buttons = (0..3).map(|i| cx.order[1 + i * 2]).collect::<Vec<_>>();
+
+        // 2 rows: each cell is (500 - 20) / 2 wide and (500 - 10) / 2 high
+        let cell = |column: f32, row: f32| Rect::new(column * 260., row * 255., 240., 245.);
+
+        assert_eq!(cx.bounds_of(buttons[0]), Some(cell(0., 0.)));
+        assert_eq!(cx.bounds_of(buttons[1]), Some(cell(1., 0.)));
+        assert_eq!(cx.bounds_of(buttons[2]), Some(cell(0., 1.)));
+
+        // a single row once an item is gone
+        items.set(vec![1, 2]);
+        cx.build(&view);
+        cx.layout(&view);
+
+        assert_eq!(cx.bounds_of(buttons[0]), Some(Rect::new(0., 0., 240., 500.)));
+        assert_eq!(cx.bounds_of(buttons[1]), Some(Rect::new(260., 0., 240., 500.)));
+    }
+}
@@ -5,12 +5,18 @@ use aplite_types::{ImageData, ImageRef, Length, Matrix3x2, PaintRef, Rect, rgb};
 
 use crate::context::{BuildCx, LayoutCx, CursorCx};
 use crate::layout::Axis;
+use crate::view::IntoView;
 use crate::widget::{Renderable, Widget};
 
 pub fn image<F: Fn() -> ImageData + 'static>(image_fn: F) -> Image {
     Image::new(image_fn)
 }
 
+/// Create an image from pixels generated at runtime, the bytes are expected to be rgba8
+pub fn image_from_data(data: ImageData) -> Image {
+    Image { data }
+}
+
 /// This function will resize the image to 500x500 by default to optimize gpu performance.
 /// If you want to have your image bytes fully rendered, consider to use your own function
 pub fn image_reader<P: AsRef<Path>>(path: P) -> ImageData {
@@ -45,6 +51,14 @@ impl Image {
     }
 }
 
+impl IntoView for Image {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
 impl Widget for Image {
     fn build(&self, cx: &mut BuildCx<'_>) -> bool {
         cx.add_or_update_element(ImageElement::new(&self.data))
@@ -139,3 +153,17 @@ impl Renderable for ImageElement {
         }
     }
 }
+
+#[cfg(test)]
+mod image_test {
+    use super::*;
+
+    #[test]
+    fn from_image_data() {
+        let pixels = [255u8; 2 * 2 * 4];
+        let image = image_from_data(ImageData::new((2, 2), &pixels)).into_view();
+
+        assert_eq!((image.data.width, image.data.height), (2, 2));
+        assert_eq!(image.data.bytes.as_ref(), pixels.as_slice());
+    }
+}
@@ -47,27 +47,28 @@ impl<T: Widget + 'static, const N: usize> Widget for [T; N] {
     }
 }
 
-impl<T: Widget> IntoView for Vec<T> {
-    type View = Self;
+/// Children built dynamically, collect an iterator of views into a [`Vec`] to lay them out as siblings
+impl<IV: IntoView> IntoView for Vec<IV> {
+    type View = Vec<IV::View>;
 
     fn into_view(self) -> Self::View {
-        self
+        self.into_iter().map(IntoView::into_view).collect()
     }
 }
 
-impl<T: Widget + 'static> IntoView for Box<[T]> {
-    type View = Self;
+impl<IV: IntoView> IntoView for Box<[IV]> {
+    type View = Box<[IV::View]>;
 
     fn into_view(self) -> Self::View {
-        self
+        self.into_iter().map(IntoView::into_view).collect()
     }
 }
 
-impl<T: Widget + 'static, const N: usize> IntoView for [T; N] {
-    type View = Self;
+impl<IV: IntoView, const N: usize> IntoView for [IV; N] {
+    type View = [IV::View; N];
 
     fn into_view(self) -> Self::View {
-        self
+        self.map(IntoView::into_view)
     }
 }
 
@@ -88,6 +89,20 @@ fn build<T: Widget>(this: &[T], cx: &mut BuildCx<'_>) -> bool {
 fn layout<T: Widget>(this: &[T], cx: &mut LayoutCx<'_>) {
     let count = this.len();
 
+    if let Some(grid) = cx.rules.grid {
+        let (bound, rules) = (cx.bound, cx.rules);
+        let mut path_id = cx.pop();
+
+        this.iter().enumerate().for_each(|(index, w)| {
+            let mut cx = LayoutCx::derive(cx, rules, grid.cell(bound, index, count));
+            cx.layout_child(path_id, w);
+            path_id += 1;
+        });
+
+        cx.push(path_id);
+        return;
+    }
+
     let bound = match cx.rules.axis {
         Axis::Horizontal => {
             let width = cx.bound.width / count as f32;
@@ -104,7 +119,7 @@ fn layout<T: Widget>(this: &[T], cx: &mut LayoutCx<'_>) {
     let mut path_id = cx.pop();
 
     this.iter().for_each(|w| {
-        cx.with_id(path_id, |cx| w.layout(cx));
+        cx.layout_child(path_id, w);
         path_id += 1;
     });
 
@@ -112,15 +127,42 @@ fn layout<T: Widget>(this: &[T], cx: &mut LayoutCx<'_>) {
 }
 
 fn detect_hover<T: Widget>(this: &[T], cx: &mut CursorCx<'_>) -> bool {
-    let mut id_path = cx.pop();
+    let id_path = cx.pop();
 
-    let hovered = this.iter().any(|widget| {
-        let content_hovered = cx.with_id(id_path, |cx| widget.detect_hover(cx));
-        id_path += 1;
-        content_hovered
-    });
+    let hovered = cx.hit_order(id_path, this.len())
+        .into_iter()
+        .any(|offset| cx.with_id(id_path + offset, |cx| this[offset as usize].detect_hover(cx)));
 
-    cx.push(id_path);
+    cx.push(id_path + this.len() as u32);
 
     hovered
 }
+
+#[cfg(test)]
+mod iterables_test {
+    use super::*;
+    use crate::context::Context;
+    use crate::widget::{button, vstack};
+
+    #[test]
+    fn stack_from_vec() {
+        let buttons = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| button(name, || {}))
+            .collect::<Vec<_>>();
+
+        let view = vstack(buttons).into_view();
+        let mut cx = Context::new((500, 300).into());
+        cx.build(&view);
+        cx.layout(&view);
+
+        // stack, then every button followed by its content
+        assert_eq!(cx.order.len(), 7);
+
+        let children = [cx.order[1], cx.order[3], cx.order[5]]
+            .map(|id| cx.bounds_of(id).unwrap());
+
+        assert!(children[0].y < children[1].y);
+        assert!(children[1].y < children[2].y);
+    }
+}
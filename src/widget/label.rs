@@ -0,0 +1,148 @@
+use aplite_renderer::{Scene, measure_text};
+use aplite_types::{Color, Rect};
+
+use crate::context::{BuildCx, LayoutCx, CursorCx};
+use crate::layout::Axis;
+use crate::state::FontSize;
+use crate::theme::Theme;
+use crate::view::IntoView;
+use crate::widget::{Renderable, Widget};
+
+/// Static text sized to fit its content, measured with the default font
+pub fn label(text: &str) -> Label {
+    Label {
+        text: text.to_string(),
+        style_fn: None,
+    }
+}
+
+pub struct Label {
+    text: String,
+
+    #[allow(clippy::type_complexity)]
+    style_fn: Option<Box<dyn Fn(&mut LabelElement)>>,
+}
+
+impl Label {
+    pub fn style(self, style_fn: impl Fn(&mut LabelElement) + 'static) -> Self {
+        Self {
+            style_fn: Some(Box::new(style_fn)),
+            ..self
+        }
+    }
+}
+
+impl IntoView for Label {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+impl Widget for Label {
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        let mut elem = LabelElement {
+            text: self.text.clone(),
+            ..LabelElement::new(cx.theme())
+        };
+
+        if let Some(style_fn) = self.style_fn.as_ref() {
+            style_fn(&mut elem);
+        }
+
+        cx.add_or_update_element(elem)
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        let elem = cx.get_element::<LabelElement>().unwrap();
+        let size = measure_text(&elem.text, elem.font_size.0);
+        let bound = cx.bound;
+
+        let node = Rect::new(bound.x, bound.y, size.width, size.height);
+
+        match cx.rules.axis {
+            Axis::Horizontal => {
+                cx.bound.x += size.width + cx.rules.spacing.0 as f32;
+            },
+            Axis::Vertical =>  {
+                cx.bound.y += size.height + cx.rules.spacing.0 as f32;
+            },
+        }
+
+        cx.set_node(node);
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        let rect = cx.get_layout_node().unwrap();
+        let hovered = rect.contains(cx.hover_pos());
+
+        if hovered {
+            cx.set_id();
+        }
+
+        hovered
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelElement {
+    pub color: Color,
+    pub font_size: FontSize,
+    text: String,
+}
+
+impl LabelElement {
+    fn new(theme: Option<Theme>) -> Self {
+        Self {
+            color: theme.unwrap_or_default().foreground,
+            font_size: FontSize(16.),
+            text: String::new(),
+        }
+    }
+}
+
+impl Renderable for LabelElement {
+    fn render(&self, rect: &Rect, scene: &mut Scene) {
+        scene.draw_text(
+            &self.text,
+            self.font_size.0,
+            rect,
+            &self.color,
+        );
+    }
+
+    fn equal(&self, other: &dyn Renderable) -> bool {
+        if other.type_id() == self.type_id() {
+            unsafe {
+                let ptr = other as *const dyn Renderable as *const Self;
+                return (&*ptr).eq(self)
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod label_test {
+    use super::*;
+    use crate::context::Context;
+
+    fn label_width(text: &str) -> f32 {
+        let view = label(text);
+        let mut cx = Context::new((500, 500).into());
+        cx.build(&view);
+        cx.layout(&view);
+        cx.layout_nodes[0].width
+    }
+
+    #[test]
+    fn width_scales_with_length() {
+        let short = label_width("abc");
+        let long = label_width("abcabc");
+
+        assert!(short > 0.);
+        assert!(long > short * 1.8 && long < short * 2.2, "{short} {long}");
+    }
+}
@@ -0,0 +1,224 @@
+use std::cell::RefCell;
+
+use aplite_reactive::{Get, Scope, Signal};
+
+use crate::context::{BuildCx, LayoutCx, CursorCx, ViewId};
+use crate::view::IntoView;
+use crate::widget::Widget;
+
+/// Show `child` while `when` is true, `fallback` otherwise. A hidden child is detached from
+/// the render tree but keeps its nodes, and is attached again with the same ids.
+/// Each view is built when its side turns active, and dropped along with its reactive scope when it turns inactive
+pub fn show<C, F, VC, VF>(when: Signal<bool>, child: C, fallback: F) -> Show<C, F, VC::View, VF::View>
+where
+    C: Fn() -> VC + 'static,
+    F: Fn() -> VF + 'static,
+    VC: IntoView,
+    VF: IntoView,
+{
+    Show {
+        when,
+        child,
+        fallback,
+        shown: RefCell::new(None),
+        hidden: RefCell::new(None),
+        detached: RefCell::new(Vec::new()),
+    }
+}
+
+pub struct Show<C, F, VC, VF> {
+    when: Signal<bool>,
+    child: C,
+    fallback: F,
+    /// The child view while `when` is true
+    shown: RefCell<Option<Branch<VC>>>,
+    /// The fallback view while `when` is false
+    hidden: RefCell<Option<Branch<VF>>>,
+    /// Ids of the child subtree from the last time it was shown
+    detached: RefCell<Vec<ViewId>>,
+}
+
+struct Branch<V> {
+    scope: Scope,
+    view: V,
+}
+
+impl<V> Branch<V> {
+    fn new<IV: IntoView<View = V>>(view_fn: impl FnOnce() -> IV) -> Self {
+        let scope = Scope::new();
+        let view = scope.with(|| view_fn().into_view());
+        Self { scope, view }
+    }
+}
+
+impl<V> Drop for Branch<V> {
+    fn drop(&mut self) {
+        self.scope.cleanup();
+    }
+}
+
+impl<C, F, VC, VF> Show<C, F, VC::View, VF::View>
+where
+    C: Fn() -> VC + 'static,
+    F: Fn() -> VF + 'static,
+    VC: IntoView,
+    VF: IntoView,
+{
+    /// Build the view of the active side if it isn't yet, and drop the other one
+    fn update(&self) -> bool {
+        let when = self.when.get_untracked();
+
+        if when {
+            self.hidden.borrow_mut().take();
+            self.shown.borrow_mut().get_or_insert_with(|| Branch::new(&self.child));
+        } else {
+            self.shown.borrow_mut().take();
+            self.hidden.borrow_mut().get_or_insert_with(|| Branch::new(&self.fallback));
+        }
+
+        when
+    }
+}
+
+impl<C, F, VC, VF> Widget for Show<C, F, VC::View, VF::View>
+where
+    C: Fn() -> VC + 'static,
+    F: Fn() -> VF + 'static,
+    VC: IntoView,
+    VF: IntoView,
+{
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        if self.update() {
+            let shown = self.shown.borrow();
+            let view = &shown.as_ref().unwrap().view;
+            let (dirty, ids) = cx.track(|cx| cx.with_id(0, |cx| view.build(cx)));
+            *self.detached.borrow_mut() = ids;
+            dirty
+        } else {
+            cx.keep_alive(&self.detached.borrow());
+            let hidden = self.hidden.borrow();
+            cx.with_id(1, |cx| hidden.as_ref().unwrap().view.build(cx))
+        }
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        if let Some(shown) = self.shown.borrow().as_ref() {
+            cx.layout_only_child(0, &shown.view);
+        } else if let Some(hidden) = self.hidden.borrow().as_ref() {
+            cx.layout_only_child(1, &hidden.view);
+        }
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        if let Some(shown) = self.shown.borrow().as_ref() {
+            cx.with_id(0, |cx| shown.view.detect_hover(cx))
+        } else if let Some(hidden) = self.hidden.borrow().as_ref() {
+            cx.with_id(1, |cx| hidden.view.detect_hover(cx))
+        } else {
+            false
+        }
+    }
+}
+
+impl<C, F, VC, VF> IntoView for Show<C, F, VC::View, VF::View>
+where
+    C: Fn() -> VC + 'static,
+    F: Fn() -> VF + 'static,
+    VC: IntoView,
+    VF: IntoView,
+{
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+#[cfg(test)]
+mod show_test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use aplite_reactive::{Set, on_cleanup};
+    use super::*;
+    use crate::context::Context;
+    use crate::view::{IdentifyView, WidgetId};
+    use crate::widget::test_util::{Probe, label_of};
+
+    fn build(cx: &mut Context, view: &impl Widget) {
+        cx.build(view);
+        // redraw phase
+        cx.build(view);
+    }
+
+    #[test]
+    fn reattach_with_same_id() {
+        let when = Signal::new(true);
+        let child_id = WidgetId::new();
+        let fallback_id = WidgetId::new();
+
+        let (c, f) = (child_id.clone(), fallback_id.clone());
+        let view = show(
+            when,
+            move || Probe::new("child").id(&c),
+            move || Probe::new("fallback").id(&f),
+        );
+        let mut cx = Context::new((500, 500).into());
+
+        build(&mut cx, &view);
+        let shown = child_id.get().unwrap();
+        assert!(cx.order.contains(&shown));
+        assert!(fallback_id.get().is_none());
+
+        when.set(false);
+        build(&mut cx, &view);
+        let hidden = fallback_id.get().unwrap();
+        assert!(!cx.order.contains(&shown));
+        assert!(cx.order.contains(&hidden));
+        assert_ne!(shown, hidden);
+        // the detached child keeps its element
+        assert_eq!(label_of(cx.elements[shown.0 as usize].as_ref()), Some("child"));
+
+        when.set(true);
+        build(&mut cx, &view);
+        assert_eq!(child_id.get(), Some(shown));
+        assert!(cx.order.contains(&shown));
+        assert!(!cx.order.contains(&hidden));
+    }
+
+    #[test]
+    fn views_built_once_per_flip() {
+        let when = Signal::new(true);
+        let (children, cleanups) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+
+        let (c, d) = (children.clone(), cleanups.clone());
+        let view = show(
+            when,
+            move || {
+                c.set(c.get() + 1);
+                let d = d.clone();
+                on_cleanup(move || d.set(d.get() + 1));
+                Probe::new("child")
+            },
+            || Probe::new("fallback"),
+        );
+        let mut cx = Context::new((500, 500).into());
+
+        for _ in 0..2 {
+            build(&mut cx, &view);
+            cx.layout(&view);
+            cx.handle_mouse_move((5., 5.), &view);
+        }
+        assert_eq!((children.get(), cleanups.get()), (1, 0));
+
+        when.set(false);
+        build(&mut cx, &view);
+        assert_eq!((children.get(), cleanups.get()), (1, 1));
+
+        when.set(true);
+        build(&mut cx, &view);
+        assert_eq!((children.get(), cleanups.get()), (2, 1));
+
+        drop(view);
+        assert_eq!(cleanups.get(), 2);
+    }
+}
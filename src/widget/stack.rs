@@ -1,11 +1,11 @@
 use std::marker::PhantomData;
 use aplite_renderer::Scene;
-use aplite_types::{Color, CornerRadius, Length, Matrix3x2, PaintRef, Rect};
+use aplite_types::{Color, CornerRadius, Length, PaintRef, Rect};
 use aplite_types::theme::basic;
 
-use crate::layout::{AlignH, AlignV, Axis, LayoutRules, Padding, Spacing};
+use crate::layout::{AlignH, AlignV, Axis, LayoutRules, Margin, Padding, Spacing};
 use crate::context::{CursorCx, BuildCx, LayoutCx};
-use crate::state::BorderWidth;
+use crate::state::{BorderWidth, Rotation};
 use crate::view::IntoView;
 use crate::widget::{Renderable, Widget};
 
@@ -73,10 +73,7 @@ where
     AX: StackDirection + 'static,
 {
     fn build(&self, cx: &mut BuildCx<'_>) -> bool {
-        let mut elem = StackElement {
-            z_index: cx.get_z_index(),
-            ..StackElement::new()
-        };
+        let mut elem = StackElement::new();
 
         if let Some(style_fn) = self.style_fn.as_ref() {
             style_fn(&mut elem);
@@ -90,15 +87,16 @@ where
 
     fn layout(&self, cx: &mut LayoutCx<'_>) {
         let state = cx.get_element::<StackElement>().unwrap();
+        let margin = state.margin;
 
         let width = match state.width {
-            Length::Grow => cx.bound.width,
+            Length::Grow => cx.bound.width - margin.horizontal() as f32,
             Length::Fixed(val) => val,
             Length::FitContent => 0.,
         };
 
         let height = match state.height {
-            Length::Grow => cx.bound.height,
+            Length::Grow => cx.bound.height - margin.vertical() as f32,
             Length::Fixed(val) => val,
             Length::FitContent => 0.,
         };
@@ -109,21 +107,22 @@ where
             align_h: state.align_h,
             align_v: state.align_v,
             spacing: state.spacing,
+            grid: None,
         };
 
         let layout_node = Rect::new(
-            cx.bound.x,
-            cx.bound.y,
+            cx.bound.x + margin.left as f32,
+            cx.bound.y + margin.top as f32,
             width,
             height,
         );
 
         match cx.rules.axis {
             Axis::Horizontal => {
-                cx.bound.x += width + cx.rules.spacing.0 as f32;
+                cx.bound.x += width + margin.horizontal() as f32 + cx.rules.spacing.0 as f32;
             },
             Axis::Vertical =>  {
-                cx.bound.y += height + cx.rules.spacing.0 as f32;
+                cx.bound.y += height + margin.vertical() as f32 + cx.rules.spacing.0 as f32;
             },
         }
 
@@ -150,7 +149,7 @@ where
 
         let mut cx = LayoutCx::derive(cx, rules, bound);
 
-        cx.with_id(0, |cx| self.content.layout(cx));
+        cx.layout_child(0, &self.content);
     }
 
     fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
@@ -182,11 +181,14 @@ pub struct StackElement {
     pub border_color: Color,
     pub border_width: BorderWidth,
     pub corner_radius: CornerRadius,
+    pub rotation: Rotation,
     pub padding: Padding,
+    pub margin: Margin,
     pub spacing: Spacing,
     pub align_h: AlignH,
     pub align_v: AlignV,
-    z_index: u32,
+    /// Paint & hit-test order among siblings, higher is on top
+    pub z_index: i32,
 }
 
 impl std::fmt::Debug for StackElement {
@@ -206,19 +208,29 @@ impl StackElement {
             border_width: BorderWidth(0.),
             corner_radius: CornerRadius::splat(0),
             padding: Padding::splat(0),
+            margin: Margin::splat(0),
             align_h: AlignH::Left,
             align_v: AlignV::Top,
             spacing: Spacing(0),
+            rotation: Rotation(0.),
             z_index: 0,
         }
     }
+
+    pub fn rotate(&mut self, radians: f32) {
+        self.rotation.0 += radians;
+    }
 }
 
 impl Renderable for StackElement {
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
     fn render(&self, rect: &Rect, scene: &mut Scene) {
         scene.draw_rounded_rect(
             rect,
-            &Matrix3x2::identity(),
+            &self.rotation.transform(),
             &PaintRef::from(&self.background),
             &PaintRef::from(&self.border_color),
             &self.border_width.0,
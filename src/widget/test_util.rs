@@ -0,0 +1,176 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use aplite_reactive::{Get, Signal};
+use aplite_renderer::Scene;
+use aplite_types::Rect;
+
+use crate::context::{BuildCx, CursorCx, LayoutCx, ViewId};
+use crate::view::IntoView;
+use crate::widget::{Renderable, Widget};
+
+/// The labels & ids of the probes in build order
+pub(crate) type Built = Rc<RefCell<Vec<(String, ViewId)>>>;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct ProbeElement {
+    pub(crate) label: String,
+    pub(crate) height: f32,
+    pub(crate) z_index: i32,
+}
+
+impl ProbeElement {
+    pub(crate) fn downcast(element: &dyn Renderable) -> Option<&Self> {
+        (element.type_id() == std::any::TypeId::of::<Self>())
+            .then(|| unsafe { &*(element as *const dyn Renderable as *const Self) })
+    }
+}
+
+impl Renderable for ProbeElement {
+    fn render(&self, _rect: &Rect, _scene: &mut Scene) {}
+
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    fn equal(&self, other: &dyn Renderable) -> bool {
+        Self::downcast(other).is_some_and(|other| other == self)
+    }
+}
+
+/// Test widget adding a [`ProbeElement`] with the given label. Unless it has a fixed node, it is
+/// laid out 10 wide and as high as its height signal, pushing the next sibling down.
+/// It is hovered whenever the cursor is inside of its node
+pub(crate) struct Probe {
+    label: String,
+    built: Option<Built>,
+    height: Option<Signal<f32>>,
+    layouts: Option<Rc<Cell<u32>>>,
+    node: Option<Rect>,
+    z_index: i32,
+}
+
+impl Probe {
+    pub(crate) fn new(label: impl ToString) -> Self {
+        Self {
+            label: label.to_string(),
+            built: None,
+            height: None,
+            layouts: None,
+            node: None,
+            z_index: 0,
+        }
+    }
+
+    /// Always lay out at `node` without moving the bound, so siblings can overlap
+    pub(crate) fn node(self, node: Rect) -> Self {
+        Self {
+            node: Some(node),
+            ..self
+        }
+    }
+
+    pub(crate) fn z_index(self, z_index: i32) -> Self {
+        Self {
+            z_index,
+            ..self
+        }
+    }
+
+    /// Tracked while building, a change makes the element dirty
+    pub(crate) fn height(self, height: Signal<f32>) -> Self {
+        Self {
+            height: Some(height),
+            ..self
+        }
+    }
+
+    /// Count the layout passes into `layouts`
+    pub(crate) fn count_layouts(self, layouts: &Rc<Cell<u32>>) -> Self {
+        Self {
+            layouts: Some(layouts.clone()),
+            ..self
+        }
+    }
+
+    /// Push the label & id into `built` on every build
+    pub(crate) fn record(self, built: &Built) -> Self {
+        Self {
+            built: Some(built.clone()),
+            ..self
+        }
+    }
+}
+
+impl Widget for Probe {
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        let dirty = cx.add_or_update_element(ProbeElement {
+            label: self.label.clone(),
+            height: self.height.map(|height| height.get()).unwrap_or_default(),
+            z_index: self.z_index,
+        });
+
+        if let Some(built) = self.built.as_ref() {
+            built.borrow_mut().push((self.label.clone(), *cx.get_id().unwrap()));
+        }
+
+        dirty
+    }
+
+    fn layout(&self, cx: &mut LayoutCx<'_>) {
+        if let Some(layouts) = self.layouts.as_ref() {
+            layouts.set(layouts.get() + 1);
+        }
+
+        if let Some(node) = self.node {
+            return cx.set_node(node);
+        }
+
+        let height = cx.get_element::<ProbeElement>().unwrap().height;
+        cx.set_node(Rect::new(cx.bound.x, cx.bound.y, 10., height));
+        cx.bound.y += height;
+    }
+
+    fn detect_hover(&self, cx: &mut CursorCx<'_>) -> bool {
+        let hovered = cx.get_layout_node().is_some_and(|node| node.contains(cx.hover_pos()));
+        if hovered {
+            cx.set_id();
+        }
+        hovered
+    }
+}
+
+impl IntoView for Probe {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+/// The label of the probe rendering `element`, if it is one
+pub(crate) fn label_of(element: &dyn Renderable) -> Option<&str> {
+    ProbeElement::downcast(element).map(|probe| probe.label.as_str())
+}
+
+/// Reads the `u32` provided via `use_context` on every build, without adding an element
+pub(crate) struct ReadContext(pub(crate) Rc<Cell<Option<u32>>>);
+
+impl Widget for ReadContext {
+    fn build(&self, cx: &mut BuildCx<'_>) -> bool {
+        self.0.set(cx.use_context::<u32>());
+        false
+    }
+
+    fn layout(&self, _cx: &mut LayoutCx<'_>) {}
+
+    fn detect_hover(&self, _cx: &mut CursorCx<'_>) -> bool { false }
+}
+
+impl IntoView for ReadContext {
+    type View = Self;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
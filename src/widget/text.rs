@@ -2,7 +2,6 @@ use std::borrow::Cow;
 
 use aplite_renderer::Scene;
 use aplite_types::{
-    Matrix3x2,
     Rect,
     Color,
     rgb
@@ -12,6 +11,7 @@ use crate::view::IntoView;
 use crate::widget::{Widget, Renderable};
 use crate::context::{BuildCx, LayoutCx, CursorCx};
 use crate::layout::{AlignH, AlignV, Axis};
+use crate::theme::Theme;
 
 pub fn text<IV>(text: IV) -> Text<IV>
 where
@@ -56,7 +56,7 @@ where
     fn build(&self, cx: &mut BuildCx<'_>) -> bool {
         let mut text_element = TextElement {
             text: Cow::from(self.text.to_string()),
-            ..Default::default()
+            ..TextElement::themed(cx.theme())
         };
 
         if let Some(style_fn) = self.style_fn.as_ref() {
@@ -176,6 +176,18 @@ impl Default for TextElement {
     }
 }
 
+impl TextElement {
+    fn themed(theme: Option<Theme>) -> Self {
+        match theme {
+            Some(theme) => Self {
+                color: theme.foreground,
+                ..Default::default()
+            },
+            None => Self::default(),
+        }
+    }
+}
+
 impl PartialEq for TextElement {
     fn eq(&self, other: &Self) -> bool {
         self.text.as_ref().eq(other.text.as_ref())
@@ -200,7 +212,6 @@ impl Renderable for TextElement {
             self.text.as_ref(),
             self.size,
             rect,
-            &Matrix3x2::identity(),
             &self.color
         );
     }
@@ -223,7 +234,7 @@ macro_rules! impl_display_primitive {
             fn build(&self, cx: &mut BuildCx<'_>) -> bool {
                 let text_element = TextElement {
                     text: Cow::from(self.to_string()),
-                    ..Default::default()
+                    ..TextElement::themed(cx.theme())
                 };
 
                 cx.add_or_update_element(text_element)